@@ -9,11 +9,24 @@
 //! This module actually contains conditional selection implementations as
 //! well as equalty-checking gadgets.
 use super::AllocatedScalar;
+use crate::range::{bit_decomposition, range_gadget, MAX_BITS};
 use crate::Error as GadgetsError;
+use alloc::vec;
+use alloc::vec::Vec;
 use dusk_plonk::prelude::*;
 
 /// Constrain `x` to be a bit
 pub fn bit_gadget(composer: &mut StandardComposer, x: AllocatedScalar) -> Result<(), GadgetsError> {
+    bit_gadget_with_complement(composer, x)?;
+    Ok(())
+}
+
+/// Constrain `x` to be a bit, returning its complement `1 - x`, which is
+/// allocated and constrained as part of the bit check anyway.
+pub fn bit_gadget_with_complement(
+    composer: &mut StandardComposer,
+    x: AllocatedScalar,
+) -> Result<AllocatedScalar, GadgetsError> {
     let one_minus_x = AllocatedScalar::allocate(composer, BlsScalar::one() - x.scalar);
 
     // constrain x + one_minux_x = 1
@@ -37,5 +50,348 @@ pub fn bit_gadget(composer: &mut StandardComposer, x: AllocatedScalar) -> Result
         BlsScalar::zero(),
         None,
     );
+    Ok(one_minus_x)
+}
+
+/// Constrain `bits` to be the little-endian binary decomposition of `value`:
+/// each of the provided bits is constrained to be a bit, and their weighted sum
+/// b_0 * 2^0 + b_1 * 2^1 + ... + b_n * 2^n is constrained to be equal to `value`.
+/// Unlike `range_gadget`, the bits are not allocated by the gadget but provided
+/// by the caller, so they can be reused elsewhere in the circuit.
+pub fn assert_bit_decomposition_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    bits: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    if bits.len() > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    let mut accumulator: Variable = composer.zero_var();
+    for (power, bit) in bits.iter().enumerate() {
+        composer.boolean_gate(bit.var);
+
+        let two_pow = BlsScalar::from(2).pow(&[power as u64, 0, 0, 0]);
+        accumulator = composer.add(
+            (two_pow, bit.var),
+            (BlsScalar::one(), accumulator),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.assert_equal(accumulator, value.var);
+
+    Ok(())
+}
+
+/// Constrain the boolean formula in conjunctive normal form described by `clauses`
+/// to be satisfied. Each clause is a list of `(variable, negated)` literals, and
+/// it's satisfied if at least one of them is true, which is proven by
+/// constraining the product of the negations of its literals to be zero.
+/// Each variable is constrained to be a bit every time it appears in a literal.
+pub fn cnf_satisfied_gadget(
+    composer: &mut StandardComposer,
+    clauses: &Vec<Vec<(AllocatedScalar, bool)>>,
+) -> Result<(), GadgetsError> {
+    let one = composer.add_witness_to_circuit_description(BlsScalar::one());
+    for clause in clauses.iter() {
+        let mut all_false = one;
+        for (x, negated) in clause.iter() {
+            let one_minus_x = bit_gadget_with_complement(composer, *x)?;
+            // The negation of the literal is `x` if it's negated and `1 - x` otherwise
+            let literal_false = if *negated { x.var } else { one_minus_x.var };
+            all_false = composer.mul(
+                BlsScalar::one(),
+                all_false,
+                literal_false,
+                BlsScalar::zero(),
+                None,
+            );
+        }
+        composer.constrain_to_constant(all_false, BlsScalar::zero(), None);
+    }
+
+    Ok(())
+}
+
+/// Returns the bitwise NOT of `value` within `n_bits` bits, i.e. (2^n_bits - 1) - value.
+/// The value is range-checked to `n_bits` bits first, since otherwise the
+/// subtraction wouldn't correspond to flipping its bits.
+pub fn bitwise_not_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    range_gadget(composer, value, n_bits)?;
+
+    let mask = BlsScalar::from(2).pow(&[n_bits as u64, 0, 0, 0]) - BlsScalar::one();
+    Ok(AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), value.var),
+            (BlsScalar::zero(), value.var),
+            mask,
+            None,
+        ),
+        scalar: mask - value.scalar,
+    })
+}
+
+/// Constrain `bits` to be a valid set of flags where each of the `groups` is
+/// mutually exclusive, i.e. at most one of its flags is set.
+/// Each group is given as the half-open range `(start, end)` of the indices
+/// of its bits, and the sum of them is constrained to be a bit.
+/// Every one of `bits` is constrained to be a bit as well.
+/// Returns `IndexOutOfBounds` if any of the groups doesn't fit in `bits`.
+pub fn flag_groups_gadget(
+    composer: &mut StandardComposer,
+    bits: &Vec<AllocatedScalar>,
+    groups: &[(usize, usize)],
+) -> Result<(), GadgetsError> {
+    if groups
+        .iter()
+        .any(|(start, end)| start > end || *end > bits.len())
+    {
+        return Err(GadgetsError::IndexOutOfBounds);
+    }
+
+    for bit in bits.iter() {
+        composer.boolean_gate(bit.var);
+    }
+
+    for (start, end) in groups.iter() {
+        let mut accumulator: Variable = composer.zero_var();
+        for bit in bits[*start..*end].iter() {
+            accumulator = composer.add(
+                (BlsScalar::one(), accumulator),
+                (BlsScalar::one(), bit.var),
+                BlsScalar::zero(),
+                None,
+            );
+        }
+        // Since the sum can't wrap around, it's at most one if it's a bit
+        composer.boolean_gate(accumulator);
+    }
+
+    Ok(())
+}
+
+/// Returns the XOR of the `bits` whose corresponding selector is set, computed as
+/// the least significant bit of the sum of `bits[i] * selectors[i]`.
+/// Both the bits and the selectors are constrained to be bits.
+/// Returns `LengthMismatch` if there isn't one selector per bit.
+pub fn conditional_parity_gadget(
+    composer: &mut StandardComposer,
+    bits: &Vec<AllocatedScalar>,
+    selectors: &Vec<AllocatedScalar>,
+) -> Result<AllocatedScalar, GadgetsError> {
+    if bits.len() != selectors.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let mut sum = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for (bit, selector) in bits.iter().zip(selectors.iter()) {
+        composer.boolean_gate(bit.var);
+        composer.boolean_gate(selector.var);
+        let selected = composer.mul(
+            BlsScalar::one(),
+            bit.var,
+            selector.var,
+            BlsScalar::zero(),
+            None,
+        );
+        sum.var = composer.add(
+            (BlsScalar::one(), sum.var),
+            (BlsScalar::one(), selected),
+            BlsScalar::zero(),
+            None,
+        );
+        sum.scalar += bit.scalar * selector.scalar;
+    }
+
+    // The sum is at most `bits.len()`, so it fits in as many bits as that length
+    let sum_bits = core::cmp::max((usize::BITS - bits.len().leading_zeros()) as usize, 1);
+    let decomposition = bit_decomposition(composer, sum, sum_bits)?;
+    Ok(decomposition[0])
+}
+
+/// Returns the bitwise OR of all the `values`, each of which is decomposed into
+/// `n_bits` bits (which proves it fits in them). The bits at each position are
+/// OR-ed pairwise as a OR b = a + b - a * b, and the result is recomposed.
+/// The OR of an empty vector is zero.
+pub fn bitwise_or_reduce_gadget(
+    composer: &mut StandardComposer,
+    values: &Vec<AllocatedScalar>,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    let zero = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    let mut or_bits = vec![zero; n_bits];
+    for value in values.iter() {
+        let bits = bit_decomposition(composer, *value, n_bits)?;
+        for (or_bit, bit) in or_bits.iter_mut().zip(bits.iter()) {
+            let scalar = or_bit.scalar + bit.scalar - or_bit.scalar * bit.scalar;
+            let var = composer.add_input(scalar);
+            // or_bit + bit - or_bit * bit - var = 0
+            composer.poly_gate(
+                or_bit.var,
+                bit.var,
+                var,
+                -BlsScalar::one(),
+                BlsScalar::one(),
+                BlsScalar::one(),
+                -BlsScalar::one(),
+                BlsScalar::zero(),
+                None,
+            );
+            *or_bit = AllocatedScalar { var, scalar };
+        }
+    }
+
+    let mut result = zero;
+    for (i, or_bit) in or_bits.iter().enumerate() {
+        let two_pow = BlsScalar::from(2).pow(&[i as u64, 0, 0, 0]);
+        result = AllocatedScalar {
+            var: composer.add(
+                (two_pow, or_bit.var),
+                (BlsScalar::one(), result.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: result.scalar + two_pow * or_bit.scalar,
+        };
+    }
+
+    Ok(result)
+}
+
+/// Constrain the 32-bit `ip` to lie in the CIDR range `prefix/prefix_len`, i.e. its
+/// `prefix_len` most significant bits are equal to those of the public network
+/// address `prefix` (whose remaining bits are ignored). The address is decomposed
+/// into 32 bits, which also constrains it to fit in them, and the top ones are
+/// constrained to the constant bits of the prefix.
+/// Returns `BitLengthOverflow` if `prefix_len` is greater than 32.
+pub fn cidr_membership_gadget(
+    composer: &mut StandardComposer,
+    ip: AllocatedScalar,
+    prefix: u32,
+    prefix_len: u32,
+) -> Result<(), GadgetsError> {
+    if prefix_len > 32 {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    let bits = bit_decomposition(composer, ip, 32)?;
+    for (position, bit) in bits.iter().enumerate().skip(32 - prefix_len as usize) {
+        let prefix_bit = BlsScalar::from(((prefix >> position) & 1) as u64);
+        composer.constrain_to_constant(bit.var, prefix_bit, None);
+    }
+
+    Ok(())
+}
+
+/// Constrain `next` to be a Gray code successor of `prev`, i.e. both of them differ
+/// in exactly one bit. Both values are decomposed into `n_bits` bits (which proves
+/// they fit in them), the bits at each position are XOR-ed as
+/// a XOR b = a + b - 2 * a * b
+/// and the number of set bits of the XOR is constrained to be one.
+pub fn gray_code_successor_gadget(
+    composer: &mut StandardComposer,
+    prev: AllocatedScalar,
+    next: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let prev_bits = bit_decomposition(composer, prev, n_bits)?;
+    let next_bits = bit_decomposition(composer, next, n_bits)?;
+
+    let mut popcount: Variable = composer.zero_var();
+    for (a, b) in prev_bits.iter().zip(next_bits.iter()) {
+        let scalar = a.scalar + b.scalar - BlsScalar::from(2) * a.scalar * b.scalar;
+        let xor = composer.add_input(scalar);
+        // a + b - 2 * a * b - xor = 0
+        composer.poly_gate(
+            a.var,
+            b.var,
+            xor,
+            -BlsScalar::from(2),
+            BlsScalar::one(),
+            BlsScalar::one(),
+            -BlsScalar::one(),
+            BlsScalar::zero(),
+            None,
+        );
+        popcount = composer.add(
+            (BlsScalar::one(), popcount),
+            (BlsScalar::one(), xor),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.constrain_to_constant(popcount, BlsScalar::one(), None);
+
+    Ok(())
+}
+
+/// Constrain the `cells` of a `width` x `height` grid, given in row-major order,
+/// to form a checkerboard pattern, i.e. the cell at column x and row y holds
+/// the XOR of the parities of its coordinates, (x + y) mod 2.
+/// Since the pattern is known, each cell is constrained to its constant bit.
+/// Returns `LengthMismatch` if there aren't `width * height` cells.
+pub fn grid_parity_gadget(
+    composer: &mut StandardComposer,
+    cells: &Vec<AllocatedScalar>,
+    width: usize,
+    height: usize,
+) -> Result<(), GadgetsError> {
+    if cells.len() != width * height {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    for (i, cell) in cells.iter().enumerate() {
+        let (x, y) = (i % width, i / width);
+        let parity = BlsScalar::from(((x + y) % 2) as u64);
+        composer.constrain_to_constant(cell.var, parity, None);
+    }
+
+    Ok(())
+}
+
+/// Constrain `negated` to be the two's complement negation of `value` within
+/// `n_bits` bits, i.e. (2^n_bits - value) mod 2^n_bits
+/// It is computed as the bitwise NOT of `value` plus one (see `bitwise_not_gadget`),
+/// which lies in [1, 2^n_bits], so it's decomposed into `n_bits + 1` bits and the
+/// carry into the most significant one is dropped.
+pub fn twos_complement_negate_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    negated: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let not_value = bitwise_not_gadget(composer, value, n_bits)?;
+    let not_plus_one = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), not_value.var),
+            (BlsScalar::zero(), not_value.var),
+            BlsScalar::one(),
+            None,
+        ),
+        scalar: not_value.scalar + BlsScalar::one(),
+    };
+
+    // negated = not_plus_one - 2^n_bits * carry
+    let bits = bit_decomposition(composer, not_plus_one, n_bits + 1)?;
+    let two_pow_n = BlsScalar::from(2).pow(&[n_bits as u64, 0, 0, 0]);
+    let wrapped = composer.add(
+        (BlsScalar::one(), not_plus_one.var),
+        (-two_pow_n, bits[n_bits].var),
+        BlsScalar::zero(),
+        None,
+    );
+    composer.assert_equal(wrapped, negated.var);
+
     Ok(())
 }
@@ -0,0 +1,97 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Builder façade over the gadgets collection.
+//!
+//! This module contains a `GadgetBuilder` which allows to chain the gadgets
+//! applied to a `StandardComposer` while keeping track of how many
+//! constraints each one of them introduced into the circuit.
+use super::AllocatedScalar;
+use crate::range::range_gadget;
+use crate::set::{set_membership_gadget, vector_sum_gadget};
+use crate::Error as GadgetsError;
+use alloc::vec::Vec;
+use dusk_plonk::prelude::*;
+
+/// Wraps a `StandardComposer` exposing chainable methods for the gadgets,
+/// and records the amount of constraints that each of them added.
+#[derive(Debug)]
+pub struct GadgetBuilder<'a> {
+    composer: &'a mut StandardComposer,
+    report: Vec<(&'static str, usize)>,
+}
+
+impl<'a> GadgetBuilder<'a> {
+    /// Creates a new `GadgetBuilder` on top of the provided composer.
+    pub fn new(composer: &'a mut StandardComposer) -> Self {
+        GadgetBuilder {
+            composer,
+            report: Vec::new(),
+        }
+    }
+
+    /// Returns the underlying composer, e.g. to allocate new witnesses.
+    pub fn composer(&mut self) -> &mut StandardComposer {
+        self.composer
+    }
+
+    /// Constrains `value` to lie in the range [0, 2^n_bits).
+    /// See `RangeGadgets::range_gadget`.
+    pub fn range(
+        &mut self,
+        value: AllocatedScalar,
+        n_bits: usize,
+    ) -> Result<&mut Self, GadgetsError> {
+        self.track("range", |composer| range_gadget(composer, value, n_bits))
+    }
+
+    /// Constrains `value` to be a member of the public `set`.
+    /// See `SetGadgets::set_membership_gadget`.
+    pub fn membership(
+        &mut self,
+        set: &Vec<BlsScalar>,
+        value: AllocatedScalar,
+    ) -> Result<&mut Self, GadgetsError> {
+        self.track("membership", |composer| {
+            set_membership_gadget(composer, set, value)
+        })
+    }
+
+    /// Constrains the elements of `vector` to sum up to `expected_sum`.
+    /// See `SetGadgets::vector_sum_gadget`.
+    pub fn sum(
+        &mut self,
+        vector: &Vec<AllocatedScalar>,
+        expected_sum: u64,
+    ) -> Result<&mut Self, GadgetsError> {
+        self.track("sum", |composer| {
+            vector_sum_gadget(composer, vector, expected_sum)
+        })
+    }
+
+    /// Returns the name of each of the gadgets added so far, in order,
+    /// together with the amount of constraints it introduced.
+    pub fn constraint_report(&self) -> &[(&'static str, usize)] {
+        &self.report
+    }
+
+    /// Returns the total amount of constraints introduced by the gadgets added so far.
+    pub fn total_constraints(&self) -> usize {
+        self.report.iter().map(|(_, constraints)| constraints).sum()
+    }
+
+    // Applies the gadget to the composer and records the constraints it added.
+    fn track<F>(&mut self, name: &'static str, gadget: F) -> Result<&mut Self, GadgetsError>
+    where
+        F: FnOnce(&mut StandardComposer) -> Result<(), GadgetsError>,
+    {
+        let size_before = self.composer.circuit_size();
+        gadget(self.composer)?;
+        self.report
+            .push((name, self.composer.circuit_size() - size_before));
+        Ok(self)
+    }
+}
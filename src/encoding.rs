@@ -0,0 +1,387 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Encoding-related gadgets.
+//!
+//! This module contains gadgets which validate the representation of
+//! data (such as strings) encoded as vectors of scalars.
+use super::AllocatedScalar;
+use crate::range::{is_less_or_equal_gadget, range_gadget, u8_gadget, MAX_BITS};
+use crate::set::one_of_gadget;
+use crate::Error as GadgetsError;
+use alloc::vec::Vec;
+use dusk_plonk::prelude::*;
+
+// The base64 alphabet (RFC 4648), followed by the padding character.
+const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/=";
+
+/// Constrain `bytes` to be a zero-padded byte string of `length` bytes, i.e.
+/// the first `length` elements are bytes in [0, 256) and the remaining
+/// `max_len - length` elements are zero.
+/// This is done through a prefix mask m_1 >= m_2 >= ... >= m_max_len of bits
+/// summing up to `length`, which also constrains `length` to lie in [0, max_len].
+/// Returns `LengthMismatch` if `bytes` doesn't have `max_len` elements.
+pub fn length_prefixed_bytes_gadget(
+    composer: &mut StandardComposer,
+    length: AllocatedScalar,
+    bytes: &Vec<AllocatedScalar>,
+    max_len: usize,
+) -> Result<(), GadgetsError> {
+    if bytes.len() != max_len {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    // Compute the witness assignment for the length. An invalid length
+    // results in an unsatisfiable mask sum.
+    let witness_length = (0..=max_len)
+        .find(|len| BlsScalar::from(*len as u64) == length.scalar)
+        .unwrap_or(max_len);
+
+    let one = composer.add_witness_to_circuit_description(BlsScalar::one());
+    let mut previous_mask = one;
+    let zero = composer.zero_var();
+    let mut accumulator: Variable = zero;
+    for (i, byte) in bytes.iter().enumerate() {
+        let mask =
+            AllocatedScalar::allocate(composer, BlsScalar::from((i < witness_length) as u64));
+        composer.boolean_gate(mask.var);
+
+        // The mask can't be set after an unset position:
+        // mask * (1 - previous_mask) = 0
+        composer.poly_gate(
+            mask.var,
+            previous_mask,
+            zero,
+            -BlsScalar::one(),
+            BlsScalar::one(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            None,
+        );
+        previous_mask = mask.var;
+
+        // Padding bytes must be zero:
+        // byte * (1 - mask) = 0
+        composer.poly_gate(
+            byte.var,
+            mask.var,
+            zero,
+            -BlsScalar::one(),
+            BlsScalar::one(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            None,
+        );
+
+        // Since zero is a valid byte, every element can be range-checked
+        u8_gadget(composer, *byte)?;
+
+        accumulator = composer.add(
+            (BlsScalar::one(), accumulator),
+            (BlsScalar::one(), mask.var),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.assert_equal(accumulator, length.var);
+
+    Ok(())
+}
+
+/// Constrain `value` to be the LEB128 decoding of `bytes`, i.e.
+/// value = sum(d_i * 2^(7 * i))
+/// where each byte is b_i = 128 * c_i + d_i, with d_i in [0, 128). The
+/// continuation bits c_i are fixed by the length of `bytes`: every byte but the
+/// last one must have it set, and the last one must have it unset. A malformed
+/// continuation bit makes d_i fall outside of [0, 128), so the proof fails.
+/// The decoded value is also range-checked to `n_bits` bits.
+/// Returns `LengthMismatch` if `bytes` is empty, and `BitLengthOverflow` if the
+/// decoding could wrap around the field modulus, i.e. 7 * bytes.len() > MAX_BITS.
+pub fn leb128_decode_gadget(
+    composer: &mut StandardComposer,
+    bytes: &Vec<AllocatedScalar>,
+    value: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if bytes.is_empty() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+    if 7 * bytes.len() > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    let continuation = BlsScalar::from(128);
+    let mut accumulator: Variable = composer.zero_var();
+    for (i, byte) in bytes.iter().enumerate() {
+        // d_i = b_i - 128 * c_i
+        let is_last = i == bytes.len() - 1;
+        let offset = if is_last {
+            BlsScalar::zero()
+        } else {
+            -continuation
+        };
+        let digit = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), byte.var),
+                (BlsScalar::zero(), byte.var),
+                offset,
+                None,
+            ),
+            scalar: byte.scalar + offset,
+        };
+        range_gadget(composer, digit, 7)?;
+
+        let shift = BlsScalar::from(2).pow(&[7 * i as u64, 0, 0, 0]);
+        accumulator = composer.add(
+            (shift, digit.var),
+            (BlsScalar::one(), accumulator),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.assert_equal(accumulator, value.var);
+    range_gadget(composer, value, n_bits)
+}
+
+/// Constrain `runs` to be a run-length encoding of `values`, i.e. expanding each
+/// `(value, count)` pair into `count` copies of `value` reconstructs `values`.
+/// Every count is proven to be at least one, and they must add up to the length
+/// of `values`. Position j then belongs to the run r s.t.
+/// end_(r-1) <= j < end_r, where end_r = count_0 + ... + count_r,
+/// which is selected by the difference of the flags (j < end_r) - (j < end_(r-1)).
+/// The counts (and the length of `values`) are assumed to fit in `n_bits` bits.
+pub fn rle_consistency_gadget(
+    composer: &mut StandardComposer,
+    values: &Vec<AllocatedScalar>,
+    runs: &Vec<(AllocatedScalar, AllocatedScalar)>,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    // Compute the end of each run, checking that none of them is empty
+    let mut ends: Vec<AllocatedScalar> = Vec::with_capacity(runs.len());
+    let mut end = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for (_, count) in runs.iter() {
+        let count_minus_one = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), count.var),
+                (BlsScalar::zero(), count.var),
+                -BlsScalar::one(),
+                None,
+            ),
+            scalar: count.scalar - BlsScalar::one(),
+        };
+        range_gadget(composer, count_minus_one, n_bits)?;
+
+        end = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), end.var),
+                (BlsScalar::one(), count.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: end.scalar + count.scalar,
+        };
+        ends.push(end);
+    }
+    composer.constrain_to_constant(end.var, BlsScalar::from(values.len() as u64), None);
+
+    for (j, value) in values.iter().enumerate() {
+        // Since the position forms part of the circuit, j + 1 is allocated as a constant
+        let next_position = BlsScalar::from(j as u64 + 1);
+        let next_position = AllocatedScalar {
+            var: composer.add_witness_to_circuit_description(next_position),
+            scalar: next_position,
+        };
+
+        let mut previous_flag = composer.zero_var();
+        let mut accumulator = composer.zero_var();
+        for ((run_value, _), end) in runs.iter().zip(ends.iter()) {
+            // (j < end_r) = (j + 1 <= end_r)
+            let flag = is_less_or_equal_gadget(composer, next_position, *end, n_bits)?;
+            let selector = composer.add(
+                (BlsScalar::one(), flag.var),
+                (-BlsScalar::one(), previous_flag),
+                BlsScalar::zero(),
+                None,
+            );
+            let selected = composer.mul(
+                BlsScalar::one(),
+                selector,
+                run_value.var,
+                BlsScalar::zero(),
+                None,
+            );
+            accumulator = composer.add(
+                (BlsScalar::one(), accumulator),
+                (BlsScalar::one(), selected),
+                BlsScalar::zero(),
+                None,
+            );
+            previous_flag = flag.var;
+        }
+        composer.assert_equal(accumulator, value.var);
+    }
+
+    Ok(())
+}
+
+/// Constrain the string encoded in `bytes` to start with the public `prefix`, i.e.
+/// the first `prefix.len()` elements are equal to the prefix bytes, which become
+/// part of the circuit. The remaining elements are left unconstrained.
+/// Returns `LengthMismatch` if the prefix is longer than `bytes`.
+pub fn has_prefix_gadget(
+    composer: &mut StandardComposer,
+    bytes: &Vec<AllocatedScalar>,
+    prefix: &[u8],
+) -> Result<(), GadgetsError> {
+    if prefix.len() > bytes.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    for (byte, expected) in bytes.iter().zip(prefix.iter()) {
+        composer.constrain_to_constant(byte.var, BlsScalar::from(*expected as u64), None);
+    }
+
+    Ok(())
+}
+
+/// Constrain `c` to be a base64 character, i.e. one of the 64 characters of the
+/// alphabet or the padding character `=` (see `SetGadgets::one_of_gadget`).
+pub fn is_base64_char_gadget(
+    composer: &mut StandardComposer,
+    c: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    let alphabet: Vec<BlsScalar> = BASE64_ALPHABET
+        .iter()
+        .map(|byte| BlsScalar::from(*byte as u64))
+        .collect();
+    one_of_gadget(composer, c, &alphabet)
+}
+
+/// Constrain the `(value, length)` codes to form a prefix-free code, i.e. none of
+/// them is a prefix of another one, where each `value` is the `length`-bit code word
+/// itself, with 1 <= length <= n_bits.
+/// Every code is left-aligned to `n_bits` bits, covering the interval
+/// [value * 2^(n_bits - length), (value + 1) * 2^(n_bits - length))
+/// of all the words it's a prefix of. The weight 2^(n_bits - length) is selected
+/// through a one-hot vector over the possible lengths, and the interval is proven to
+/// lie in [0, 2^n_bits), which also constrains `value` to fit in `length` bits.
+/// Then, a code is a prefix of another one iff their intervals overlap, so for every
+/// pair of codes exactly one of them is proven to end before the other one starts.
+/// Returns `BitLengthOverflow` if the left-aligned codes could wrap around the
+/// field modulus, i.e. 2 * n_bits > MAX_BITS.
+pub fn prefix_free_gadget(
+    composer: &mut StandardComposer,
+    codes: &Vec<(AllocatedScalar, AllocatedScalar)>,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if 2 * n_bits > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    let two_pow_n = BlsScalar::from(2).pow(&[n_bits as u64, 0, 0, 0]);
+    let zero = composer.zero_var();
+    let mut intervals: Vec<(AllocatedScalar, AllocatedScalar)> = Vec::with_capacity(codes.len());
+    for (value, length) in codes.iter() {
+        range_gadget(composer, *value, n_bits)?;
+
+        // One-hot selection of the length, looking up its weight
+        let mut selectors_sum: Variable = zero;
+        let mut selected_length: Variable = zero;
+        let mut weight = AllocatedScalar {
+            var: zero,
+            scalar: BlsScalar::zero(),
+        };
+        for k in 1..=n_bits {
+            let selector = AllocatedScalar::allocate(
+                composer,
+                BlsScalar::from((length.scalar == BlsScalar::from(k as u64)) as u64),
+            );
+            composer.boolean_gate(selector.var);
+
+            selectors_sum = composer.add(
+                (BlsScalar::one(), selectors_sum),
+                (BlsScalar::one(), selector.var),
+                BlsScalar::zero(),
+                None,
+            );
+            selected_length = composer.add(
+                (BlsScalar::one(), selected_length),
+                (BlsScalar::from(k as u64), selector.var),
+                BlsScalar::zero(),
+                None,
+            );
+            let two_pow = BlsScalar::from(2).pow(&[(n_bits - k) as u64, 0, 0, 0]);
+            weight = AllocatedScalar {
+                var: composer.add(
+                    (BlsScalar::one(), weight.var),
+                    (two_pow, selector.var),
+                    BlsScalar::zero(),
+                    None,
+                ),
+                scalar: weight.scalar + two_pow * selector.scalar,
+            };
+        }
+        composer.constrain_to_constant(selectors_sum, BlsScalar::one(), None);
+        composer.assert_equal(selected_length, length.var);
+
+        // start = value * weight, end = start + weight
+        let start = AllocatedScalar {
+            var: composer.mul(
+                BlsScalar::one(),
+                value.var,
+                weight.var,
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: value.scalar * weight.scalar,
+        };
+        let end = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), start.var),
+                (BlsScalar::one(), weight.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: start.scalar + weight.scalar,
+        };
+
+        // 2^n_bits - end >= 0
+        let slack = AllocatedScalar {
+            var: composer.add(
+                (-BlsScalar::one(), end.var),
+                (BlsScalar::zero(), end.var),
+                two_pow_n,
+                None,
+            ),
+            scalar: two_pow_n - end.scalar,
+        };
+        range_gadget(composer, slack, n_bits + 1)?;
+
+        intervals.push((start, end));
+    }
+
+    // Since the intervals aren't empty, at most one of them can end before the other starts
+    for (i, (start_i, end_i)) in intervals.iter().enumerate() {
+        for (start_j, end_j) in intervals.iter().skip(i + 1) {
+            let i_before_j = is_less_or_equal_gadget(composer, *end_i, *start_j, n_bits + 1)?;
+            let j_before_i = is_less_or_equal_gadget(composer, *end_j, *start_i, n_bits + 1)?;
+            let disjoint = composer.add(
+                (BlsScalar::one(), i_before_j.var),
+                (BlsScalar::one(), j_before_i.var),
+                BlsScalar::zero(),
+                None,
+            );
+            composer.constrain_to_constant(disjoint, BlsScalar::one(), None);
+        }
+    }
+
+    Ok(())
+}
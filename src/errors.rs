@@ -15,4 +15,20 @@ pub enum Error {
     /// Error returned when we try to compute the inverse of a number which is
     /// non-QR (doesn't have an inverse inside of the field)
     NonExistingInverse,
+    /// Error returned when two vectors which are expected to have the same
+    /// length (i.e. they are compared or combined index by index) don't.
+    LengthMismatch,
+    /// Error returned when a gadget is asked to decompose a value into more
+    /// bits than a `BlsScalar` can hold without wrapping around the field
+    /// modulus. See `RangeGadgets::MAX_BITS`.
+    BitLengthOverflow,
+    /// Error returned when a gadget is provided with an index which points
+    /// outside of the vector it refers to.
+    IndexOutOfBounds,
+    /// Error returned when we try to compute the square root of a number
+    /// which is non-QR (doesn't have a square root inside of the field)
+    NonExistingSquareRoot,
+    /// Error returned when a sequence which is expected to be monotonically
+    /// non-decreasing contains an element lower than the previous one.
+    NonMonotonicSequence,
 }
@@ -0,0 +1,176 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Hashing-related gadgets.
+//!
+//! This module contains gadgets which prove statements about the preimages
+//! of hash functions built out of arithmetic gates.
+use super::AllocatedScalar;
+use crate::Error as GadgetsError;
+use alloc::vec::Vec;
+use dusk_plonk::prelude::*;
+
+/// Constrain `preimage` to hash into the public `digest` under the linear hash
+/// defined by `coeffs`, i.e.:
+/// c_1 * x_1 + c_2 * x_2 + ... + c_n * x_n = digest
+/// The digest is not part of the circuit, hence it's placed as Public Input.
+/// NOTE that this hash is NOT collision-resistant, so it should only be used as
+/// a building block or for testing purposes.
+pub fn linear_hash_preimage_gadget(
+    composer: &mut StandardComposer,
+    preimage: &Vec<AllocatedScalar>,
+    digest: BlsScalar,
+    coeffs: &[BlsScalar],
+) -> Result<(), GadgetsError> {
+    if preimage.len() != coeffs.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let mut accumulator: Variable = composer.zero_var();
+    for (coeff, elem) in coeffs.iter().zip(preimage.iter()) {
+        accumulator = composer.add(
+            (*coeff, elem.var),
+            (BlsScalar::one(), accumulator),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+
+    composer.constrain_to_constant(accumulator, BlsScalar::zero(), Some(-digest));
+
+    Ok(())
+}
+
+/// Constrain `value` to be the leaf at position `key_bits` of the sparse Merkle tree
+/// with the given `root`, where `path` holds the siblings from the leaf level up.
+/// At level `i`, the current node is hashed as the left child if `key_bits[i]`
+/// is 0 and as the right one otherwise, using `hash_fn`, which adds the constraints
+/// of the two-to-one hash function of the tree and returns its output.
+/// Since empty leaves hold a default value, non-membership of a key is proven
+/// by passing that default value (e.g. a constant zero) as `value`.
+/// Every one of `key_bits` is constrained to be a bit.
+/// Returns `LengthMismatch` if there isn't one key bit per level of the path.
+pub fn sparse_merkle_membership_gadget<F>(
+    composer: &mut StandardComposer,
+    key_bits: &Vec<AllocatedScalar>,
+    value: AllocatedScalar,
+    path: &Vec<AllocatedScalar>,
+    root: AllocatedScalar,
+    hash_fn: F,
+) -> Result<(), GadgetsError>
+where
+    F: Fn(
+        &mut StandardComposer,
+        AllocatedScalar,
+        AllocatedScalar,
+    ) -> Result<AllocatedScalar, GadgetsError>,
+{
+    if key_bits.len() != path.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let mut current = value;
+    for (bit, sibling) in key_bits.iter().zip(path.iter()) {
+        composer.boolean_gate(bit.var);
+
+        // left = current + bit * (sibling - current)
+        // right = sibling - bit * (sibling - current)
+        let diff = composer.add(
+            (BlsScalar::one(), sibling.var),
+            (-BlsScalar::one(), current.var),
+            BlsScalar::zero(),
+            None,
+        );
+        let swap = composer.mul(BlsScalar::one(), bit.var, diff, BlsScalar::zero(), None);
+        let swap_scalar = bit.scalar * (sibling.scalar - current.scalar);
+        let left = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), current.var),
+                (BlsScalar::one(), swap),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: current.scalar + swap_scalar,
+        };
+        let right = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), sibling.var),
+                (-BlsScalar::one(), swap),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: sibling.scalar - swap_scalar,
+        };
+
+        current = hash_fn(composer, left, right)?;
+    }
+    composer.assert_equal(current.var, root.var);
+
+    Ok(())
+}
+
+/// Returns the root of the binary Merkle tree built over all of the `leaves`,
+/// hashing every pair of sibling nodes with `hash_fn` (see
+/// `sparse_merkle_membership_gadget`) until a single node is left.
+/// If the number of leaves isn't a power of two, the tree is padded with
+/// zero leaves up to the next one.
+/// Returns `LengthMismatch` if there are no leaves.
+pub fn merkle_root_gadget<F>(
+    composer: &mut StandardComposer,
+    leaves: &Vec<AllocatedScalar>,
+    hash_fn: F,
+) -> Result<AllocatedScalar, GadgetsError>
+where
+    F: Fn(
+        &mut StandardComposer,
+        AllocatedScalar,
+        AllocatedScalar,
+    ) -> Result<AllocatedScalar, GadgetsError>,
+{
+    if leaves.is_empty() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let zero = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    let mut level: Vec<AllocatedScalar> = leaves.clone();
+    level.resize(leaves.len().next_power_of_two(), zero);
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| hash_fn(composer, pair[0], pair[1]))
+            .collect::<Result<Vec<AllocatedScalar>, GadgetsError>>()?;
+    }
+
+    Ok(level[0])
+}
+
+/// Constrain `new_acc` to be the result of adding `element` to the accumulator
+/// `old_acc`, i.e. new_acc = hash(old_acc, element)
+/// where `hash_fn` adds the constraints of the two-to-one hash function (see
+/// `sparse_merkle_membership_gadget`). Chaining this gadget proves the updates
+/// of an append-only log.
+pub fn accumulator_add_gadget<F>(
+    composer: &mut StandardComposer,
+    old_acc: AllocatedScalar,
+    element: AllocatedScalar,
+    new_acc: AllocatedScalar,
+    hash_fn: F,
+) -> Result<(), GadgetsError>
+where
+    F: Fn(
+        &mut StandardComposer,
+        AllocatedScalar,
+        AllocatedScalar,
+    ) -> Result<AllocatedScalar, GadgetsError>,
+{
+    let digest = hash_fn(composer, old_acc, element)?;
+    composer.assert_equal(digest.var, new_acc.var);
+
+    Ok(())
+}
@@ -18,6 +18,10 @@
 //!
 //! - Scalar gadgets: `is_non-zero`, `maybe_equals`, `conditionally_select_one`, `conditionally_select_zero`.
 //! - Range gadgets: `range_check`, `max_bound`.
+//! - Hash gadgets: `linear_hash_preimage`, `sparse_merkle_membership`, `merkle_root`.
+//! - Matrix gadgets: `apply_permutation`, `is_permutation_matrix`.
+//! - Encoding gadgets: `length_prefixed_bytes`, `leb128_decode`.
+//! - `GadgetBuilder`: chainable façade over the gadgets reporting the constraints each one adds.
 
 #![doc(
     html_logo_url = "https://lh3.googleusercontent.com/SmwswGxtgIANTbDrCOn5EKcRBnVdHjmYsHYxLq2HZNXWCQ9-fZyaea-bNgdX9eR0XGSqiMFi=w128-h128-e365"
@@ -35,15 +39,23 @@
 extern crate alloc;
 
 pub(crate) mod allocated_scalar;
+pub mod bit;
+pub mod builder;
+pub mod encoding;
 pub mod errors;
+pub mod hash;
+pub mod matrix;
 pub mod range;
 pub mod scalar;
 pub mod set;
-pub mod bit;
 
 pub use crate::errors::Error;
 pub use allocated_scalar::AllocatedScalar;
+pub use bit as BitGadgets;
+pub use builder::GadgetBuilder;
+pub use encoding as EncodingGadgets;
+pub use hash as HashGadgets;
+pub use matrix as MatrixGadgets;
 pub use range as RangeGadgets;
 pub use scalar as ScalarGadgets;
 pub use set as SetGadgets;
-pub use bit as BitGadgets;
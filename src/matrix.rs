@@ -0,0 +1,192 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+//! Basic gadgets for working with matrices.
+//!
+//! Matrices are represented as vectors of rows, i.e. `matrix[i][j]` is the
+//! element placed at the i-th row and the j-th column.
+use super::AllocatedScalar;
+use crate::Error as GadgetsError;
+use alloc::vec;
+use alloc::vec::Vec;
+use dusk_plonk::prelude::*;
+
+/// Constrain `output` to be the public vector `input` permuted by the private
+/// permutation matrix `perm`, i.e. output_i = sum_j(perm_ij * input_j).
+/// The permutation matrix is validated as part of the gadget: each entry is
+/// constrained to be a bit, and each row and each column to contain exactly one 1.
+/// Returns `LengthMismatch` if the dimensions of `perm`, `input` and `output` don't match.
+pub fn apply_permutation_gadget(
+    composer: &mut StandardComposer,
+    perm: &Vec<Vec<AllocatedScalar>>,
+    input: &[BlsScalar],
+    output: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    let n = input.len();
+    if output.len() != n || perm.len() != n || perm.iter().any(|row| row.len() != n) {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    permutation_matrix_constraints(composer, perm);
+
+    // Since the input vector forms part of the circuit, each of the outputs is
+    // a linear combination of the row entries with the input elements as coefficients
+    for (row, out) in perm.iter().zip(output.iter()) {
+        let mut accumulator: Variable = composer.zero_var();
+        for (entry, elem) in row.iter().zip(input.iter()) {
+            accumulator = composer.add(
+                (*elem, entry.var),
+                (BlsScalar::one(), accumulator),
+                BlsScalar::zero(),
+                None,
+            );
+        }
+        composer.assert_equal(accumulator, out.var);
+    }
+
+    Ok(())
+}
+
+/// Constrain `matrix` to be a valid permutation matrix: each entry is
+/// constrained to be a bit, and each row and each column to sum up to exactly 1.
+/// Returns `LengthMismatch` if the matrix isn't square.
+pub fn is_permutation_matrix_gadget(
+    composer: &mut StandardComposer,
+    matrix: &Vec<Vec<AllocatedScalar>>,
+) -> Result<(), GadgetsError> {
+    if matrix.iter().any(|row| row.len() != matrix.len()) {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    permutation_matrix_constraints(composer, matrix);
+
+    Ok(())
+}
+
+/// Constrain `matrix` to be symmetric, i.e. matrix[i][j] = matrix[j][i]
+/// for every entry above the diagonal.
+/// Returns `LengthMismatch` if the matrix isn't square.
+pub fn is_symmetric_gadget(
+    composer: &mut StandardComposer,
+    matrix: &Vec<Vec<AllocatedScalar>>,
+) -> Result<(), GadgetsError> {
+    if matrix.iter().any(|row| row.len() != matrix.len()) {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    for (i, row) in matrix.iter().enumerate() {
+        for (j, entry) in row.iter().enumerate().skip(i + 1) {
+            composer.assert_equal(entry.var, matrix[j][i].var);
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns the trace of `matrix`, i.e. the sum of its diagonal entries.
+/// Returns `LengthMismatch` if the matrix isn't square.
+pub fn matrix_trace_gadget(
+    composer: &mut StandardComposer,
+    matrix: &Vec<Vec<AllocatedScalar>>,
+) -> Result<AllocatedScalar, GadgetsError> {
+    if matrix.iter().any(|row| row.len() != matrix.len()) {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let mut trace = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for (i, row) in matrix.iter().enumerate() {
+        trace = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), row[i].var),
+                (BlsScalar::one(), trace.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: trace.scalar + row[i].scalar,
+        };
+    }
+
+    Ok(trace)
+}
+
+// Constrains each entry of the square `matrix` to be a bit, and each row and
+// each column to contain exactly one 1.
+pub(crate) fn permutation_matrix_constraints(
+    composer: &mut StandardComposer,
+    matrix: &[Vec<AllocatedScalar>],
+) {
+    // Each entry is a bit, and each row sums up to one
+    for row in matrix.iter() {
+        let mut accumulator: Variable = composer.zero_var();
+        for entry in row.iter() {
+            composer.boolean_gate(entry.var);
+            accumulator = composer.add(
+                (BlsScalar::one(), entry.var),
+                (BlsScalar::one(), accumulator),
+                BlsScalar::zero(),
+                None,
+            );
+        }
+        composer.constrain_to_constant(accumulator, BlsScalar::one(), None);
+    }
+
+    // Each column sums up to one
+    for j in 0..matrix.len() {
+        let mut accumulator: Variable = composer.zero_var();
+        for row in matrix.iter() {
+            accumulator = composer.add(
+                (BlsScalar::one(), row[j].var),
+                (BlsScalar::one(), accumulator),
+                BlsScalar::zero(),
+                None,
+            );
+        }
+        composer.constrain_to_constant(accumulator, BlsScalar::one(), None);
+    }
+}
+
+// Allocates the permutation matrix which maps `from` into `to`, i.e. s.t.
+// to_i = sum_j(matrix_ij * from_j). Whenever an element of `to` can't be matched
+// with an unused element of `from`, any unused one is picked instead so that the
+// matrix is still a permutation, and the constraints using it won't be satisfied.
+pub(crate) fn permutation_matrix_witness(
+    composer: &mut StandardComposer,
+    from: &[BlsScalar],
+    to: &[BlsScalar],
+) -> Vec<Vec<AllocatedScalar>> {
+    let mut used = vec![false; from.len()];
+    let mut mapping: Vec<Option<usize>> = to
+        .iter()
+        .map(|elem| {
+            let j = (0..from.len()).find(|j| !used[*j] && from[*j] == *elem)?;
+            used[j] = true;
+            Some(j)
+        })
+        .collect();
+    for target in mapping.iter_mut().filter(|target| target.is_none()) {
+        *target = (0..from.len()).find(|j| !used[*j]);
+        if let Some(j) = target {
+            used[*j] = true;
+        }
+    }
+
+    mapping
+        .iter()
+        .map(|target| {
+            (0..from.len())
+                .map(|j| {
+                    AllocatedScalar::allocate(
+                        composer,
+                        BlsScalar::from((*target == Some(j)) as u64),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
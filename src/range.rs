@@ -11,11 +11,26 @@
 //! of two, we recomend to use the function builtin plonk for it: `composer.range_gate()`
 //! since it will introduce less constraints to your CS.
 
-use super::{scalar::maybe_equal, AllocatedScalar};
+use super::{
+    bit::assert_bit_decomposition_gadget,
+    scalar::{conditionally_select_one, maybe_equal},
+    AllocatedScalar,
+};
+use crate::Error as GadgetsError;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 use dusk_bytes::Serializable;
 use dusk_plonk::prelude::*;
 
+/// Maximum amount of bits a gadget can decompose a `BlsScalar` into.
+/// Any value below 2^254 is smaller than the BLS12-381 scalar field modulus,
+/// so the recomposition of up to `MAX_BITS` bits can never wrap around it.
+pub const MAX_BITS: usize = 254;
+
+/// Default scale representing 100% for `percentage_gadget`, which expresses
+/// percentages in basis points, i.e. with two decimal digits of precision.
+pub const DEFAULT_PERCENTAGE_SCALE: u64 = 10_000;
+
 /// Returns a 0 or a 1, if the value lies within the specified range
 /// We do this by decomposing the scalar and showing that it can be represented in x amount of bits
 fn range_proof(composer: &mut StandardComposer, value: AllocatedScalar, num_bits: u64) -> Variable {
@@ -157,6 +172,1464 @@ fn scalar_decomposition_gadget(
     (is_equal, scalar_bits_var)
 }
 
+/// Constrains `value` to lie in the range [0, 2^n_bits).
+/// Unlike `range_check`, this gadget does not return a boolean result but
+/// makes the proof fail if the value can't be represented in `n_bits` bits.
+pub fn range_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    bit_decomposition(composer, value, n_bits)?;
+    Ok(())
+}
+
+/// Constrains every element of `vector` to lie in the range [0, 2^n_bits).
+/// Useful to make sure that the sum of the elements can't overflow.
+/// NOTE that `range_gadget` doesn't compute any inversion, so there is nothing
+/// to batch across the elements.
+pub fn all_in_range_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    for elem in vector.iter() {
+        range_gadget(composer, *elem, n_bits)?;
+    }
+    Ok(())
+}
+
+/// Constrains `value` to lie in the range [0, 2^8).
+pub fn u8_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    range_gadget(composer, value, 8)
+}
+
+/// Constrains `value` to lie in the range [0, 2^16).
+pub fn u16_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    range_gadget(composer, value, 16)
+}
+
+/// Constrains `value` to lie in the range [0, 2^32).
+pub fn u32_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    range_gadget(composer, value, 32)
+}
+
+/// Constrains `value` to lie in the range [0, 2^64).
+pub fn u64_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    range_gadget(composer, value, 64)
+}
+
+/// Constrains `a < b`, where both of them are private witnesses.
+/// It is done by proving that b - a - 1 lies in [0, 2^n_bits), hence
+/// both values are assumed to be representable in `n_bits` bits.
+pub fn less_than_gadget(
+    composer: &mut StandardComposer,
+    a: AllocatedScalar,
+    b: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    // Compute b - a - 1 in the circuit
+    let b_minus_a_minus_one = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), b.var),
+            (-BlsScalar::one(), a.var),
+            -BlsScalar::one(),
+            None,
+        ),
+        scalar: b.scalar - a.scalar - BlsScalar::one(),
+    };
+
+    range_gadget(composer, b_minus_a_minus_one, n_bits)
+}
+
+/// Constrains `lo < value < hi`, where the bounds are private witnesses
+/// instead of the public ones `range_check` works with.
+/// All three values are assumed to be representable in `n_bits` bits.
+pub fn between_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    lo: AllocatedScalar,
+    hi: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    less_than_gadget(composer, lo, value, n_bits)?;
+    less_than_gadget(composer, value, hi, n_bits)
+}
+
+/// Returns the absolute difference |a - b| of two private witnesses.
+/// The prover provides the sign bit `s` (1 if a >= b and 0 otherwise), and the
+/// result is constrained as d = (2s - 1) * (a - b) and range-checked to `n_bits` bits,
+/// so both values are assumed to be representable in `n_bits` bits.
+pub fn abs_diff_gadget(
+    composer: &mut StandardComposer,
+    a: AllocatedScalar,
+    b: AllocatedScalar,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    // Compute the witness assignments for the sign and the result
+    let (sign, abs_diff) = if scalar_gte(&a.scalar, &b.scalar) {
+        (BlsScalar::one(), a.scalar - b.scalar)
+    } else {
+        (BlsScalar::zero(), b.scalar - a.scalar)
+    };
+    let sign = AllocatedScalar::allocate(composer, sign);
+    composer.boolean_gate(sign.var);
+    let abs_diff = AllocatedScalar::allocate(composer, abs_diff);
+
+    // diff = a - b
+    let diff = composer.add(
+        (BlsScalar::one(), a.var),
+        (-BlsScalar::one(), b.var),
+        BlsScalar::zero(),
+        None,
+    );
+    // 2 * s * diff - diff - abs_diff = 0
+    composer.poly_gate(
+        sign.var,
+        diff,
+        abs_diff.var,
+        BlsScalar::from(2),
+        BlsScalar::zero(),
+        -BlsScalar::one(),
+        -BlsScalar::one(),
+        BlsScalar::zero(),
+        None,
+    );
+
+    // The result can only fit in `n_bits` bits if the sign was the right one
+    range_gadget(composer, abs_diff, n_bits)?;
+
+    Ok(abs_diff)
+}
+
+/// Constrains |a - b| <= tolerance, where `a` and `b` are private witnesses
+/// and the tolerance is part of the circuit.
+/// It is done by proving that tolerance - |a - b| lies in [0, 2^n_bits), hence
+/// both values (and the tolerance) are assumed to be representable in `n_bits` bits.
+pub fn approx_equal_gadget(
+    composer: &mut StandardComposer,
+    a: AllocatedScalar,
+    b: AllocatedScalar,
+    tolerance: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let abs_diff = abs_diff_gadget(composer, a, b, n_bits)?;
+
+    // Compute tolerance - |a - b| in the circuit
+    let tolerance = BlsScalar::from(tolerance);
+    let slack = AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), abs_diff.var),
+            (BlsScalar::zero(), abs_diff.var),
+            tolerance,
+            None,
+        ),
+        scalar: tolerance - abs_diff.scalar,
+    };
+
+    range_gadget(composer, slack, n_bits)
+}
+
+/// Returns 1 if `a <= b` and 0 otherwise, where both are private witnesses.
+/// It is done by decomposing b - a + 2^n_bits into `n_bits + 1` bits, whose
+/// most significant bit is set only if b >= a. Hence both values are
+/// assumed to be representable in `n_bits` bits.
+pub fn is_less_or_equal_gadget(
+    composer: &mut StandardComposer,
+    a: AllocatedScalar,
+    b: AllocatedScalar,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    // Compute b - a + 2^n_bits in the circuit
+    let offset = BlsScalar::from(2).pow(&[n_bits as u64, 0, 0, 0]);
+    let shifted_diff = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), b.var),
+            (-BlsScalar::one(), a.var),
+            offset,
+            None,
+        ),
+        scalar: b.scalar - a.scalar + offset,
+    };
+
+    let bits = bit_decomposition(composer, shifted_diff, n_bits + 1)?;
+    Ok(bits[n_bits])
+}
+
+/// Returns the `p`-th percentile of `vector` (using the nearest-rank method),
+/// i.e. its k-th smallest element, where k = ceil(p * n / 100) (or 1 for p = 0).
+/// Instead of sorting the vector, the result `r` is constrained by proving that at
+/// least `k` elements are <= r and at least `n - k + 1` elements are >= r,
+/// which can only hold for the k-th smallest element.
+/// All elements are assumed to be representable in `n_bits` bits.
+/// Returns `IndexOutOfBounds` if the vector is empty or `p` is greater than 100.
+pub fn percentile_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    p: u64,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    let n = vector.len() as u64;
+    if n == 0 || p > 100 {
+        return Err(GadgetsError::IndexOutOfBounds);
+    }
+    let rank = core::cmp::max((p * n).div_ceil(100), 1);
+
+    // Compute the witness assignment for the percentile
+    let mut sorted: Vec<BlsScalar> = vector.iter().map(|elem| elem.scalar).collect();
+    sorted.sort_by(scalar_cmp);
+    let percentile = AllocatedScalar::allocate(composer, sorted[rank as usize - 1]);
+
+    // Count the elements lower or equal and greater or equal than the percentile
+    let mut lower_count = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    let mut greater_count = lower_count;
+    for elem in vector.iter() {
+        let lower = is_less_or_equal_gadget(composer, *elem, percentile, n_bits)?;
+        let greater = is_less_or_equal_gadget(composer, percentile, *elem, n_bits)?;
+        lower_count = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), lower_count.var),
+                (BlsScalar::one(), lower.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: lower_count.scalar + lower.scalar,
+        };
+        greater_count = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), greater_count.var),
+                (BlsScalar::one(), greater.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: greater_count.scalar + greater.scalar,
+        };
+    }
+
+    // Both counts are at most `n`, so it's enough to check that
+    // count - bound fits in the amount of bits needed to represent `n`
+    let count_bits = (64 - n.leading_zeros()) as usize;
+    let min_lower = BlsScalar::from(rank);
+    let min_greater = BlsScalar::from(n - rank + 1);
+    for (count, bound) in [(lower_count, min_lower), (greater_count, min_greater)].iter() {
+        let slack = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), count.var),
+                (BlsScalar::zero(), count.var),
+                -bound,
+                None,
+            ),
+            scalar: count.scalar - bound,
+        };
+        range_gadget(composer, slack, count_bits)?;
+    }
+
+    Ok(percentile)
+}
+
+/// Constrains the vector to be sorted in non-decreasing order, i.e.
+/// v_1 <= v_2 <= ... <= v_n,
+/// by proving that each difference v_(i+1) - v_i lies in [0, 2^n_bits).
+/// NOTE that the elements are assumed to be range-checked to `n_bits` bits by
+/// the caller, otherwise the differences could wrap around the field modulus.
+pub fn is_sorted_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    for pair in vector.windows(2) {
+        // Compute v_(i+1) - v_i in the circuit
+        let diff = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), pair[1].var),
+                (-BlsScalar::one(), pair[0].var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: pair[1].scalar - pair[0].scalar,
+        };
+        range_gadget(composer, diff, n_bits)?;
+    }
+
+    Ok(())
+}
+
+/// Decomposes `value` into `n_digits` base-10 digits, least significant first,
+/// constraining each of them to lie in [0, 10) through the product
+/// d * (d - 1) * ... * (d - 9) = 0,
+/// and their recomposition to be equal to `value`. Hence the proof fails
+/// if `value` has more than `n_digits` decimal digits.
+/// Returns `BitLengthOverflow` if `n_bits` exceeds `MAX_BITS` or if the largest value
+/// of `n_digits` digits doesn't fit in `n_bits` bits, since the recomposition
+/// could then wrap around the field modulus.
+pub fn decimal_digits_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    n_digits: usize,
+    n_bits: usize,
+) -> Result<Vec<AllocatedScalar>, GadgetsError> {
+    // 10^n_digits - 1 < 2^n_bits <=> n_digits <= n_bits * log10(2)
+    if n_bits > MAX_BITS || n_digits > n_bits * 30103 / 100_000 {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    let ten = BlsScalar::from(10);
+    let mut power = BlsScalar::one();
+    let mut accumulator: Variable = composer.zero_var();
+    let mut digits: Vec<AllocatedScalar> = Vec::with_capacity(n_digits);
+    for digit in scalar_to_digits(&value.scalar, n_digits).iter() {
+        let digit = AllocatedScalar::allocate(composer, BlsScalar::from(*digit as u64));
+        digit_constraint(composer, digit);
+
+        accumulator = composer.add(
+            (power, digit.var),
+            (BlsScalar::one(), accumulator),
+            BlsScalar::zero(),
+            None,
+        );
+        power *= ten;
+        digits.push(digit);
+    }
+    composer.assert_equal(accumulator, value.var);
+
+    Ok(digits)
+}
+
+/// Constrains the base-10 `digits`, given from the most significant one to the
+/// check digit, to satisfy the Luhn checksum: every second digit starting from the
+/// right of the check digit is doubled, folding it into a single digit as
+/// 2d - 9 * f, where the bit f is set iff d >= 5, and the total is divisible by 10.
+/// Both the digits and the folded values are constrained to lie in [0, 10), which
+/// also enforces the right choice of f.
+pub fn luhn_valid_gadget(
+    composer: &mut StandardComposer,
+    digits: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    let mut sum = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for (position, digit) in digits.iter().rev().enumerate() {
+        digit_constraint(composer, *digit);
+
+        let term = if position % 2 == 1 {
+            let carry = scalar_gte(&digit.scalar, &BlsScalar::from(5));
+            let carry = AllocatedScalar::allocate(composer, BlsScalar::from(carry as u64));
+            composer.boolean_gate(carry.var);
+            // 2d - 9f
+            let folded = AllocatedScalar {
+                var: composer.add(
+                    (BlsScalar::from(2), digit.var),
+                    (-BlsScalar::from(9), carry.var),
+                    BlsScalar::zero(),
+                    None,
+                ),
+                scalar: digit.scalar.double() - BlsScalar::from(9) * carry.scalar,
+            };
+            digit_constraint(composer, folded);
+            folded
+        } else {
+            *digit
+        };
+
+        sum = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), sum.var),
+                (BlsScalar::one(), term.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: sum.scalar + term.scalar,
+        };
+    }
+
+    // The sum is at most 9 * len, so its quotient by 10 comfortably fits in 32 bits
+    let remainder = mod_reduce_gadget(composer, sum, 10, 32)?;
+    composer.constrain_to_constant(remainder.var, BlsScalar::zero(), None);
+
+    Ok(())
+}
+
+/// Constrains `year`, `month` and `day` to form a valid date of the Gregorian
+/// calendar, i.e. month lies in [1, 12] and day in [1, days_in_month].
+/// The month is selected with a one-hot vector of 12 bits, which looks up the
+/// length of the month in a constant table. February gets an extra day in leap
+/// years, whose flag is computed from the divisibility flags of the year as
+/// leap = (4 | year) - (100 | year) + (400 | year)
+/// The year is assumed to be representable in `n_bits` bits.
+pub fn valid_date_gadget(
+    composer: &mut StandardComposer,
+    year: AllocatedScalar,
+    month: AllocatedScalar,
+    day: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    const DAYS_IN_MONTH: [u64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+    // Leap year flag
+    let zero = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    let mut leap = zero;
+    for (divisor, sign) in [
+        (4, BlsScalar::one()),
+        (100, -BlsScalar::one()),
+        (400, BlsScalar::one()),
+    ]
+    .iter()
+    {
+        let remainder = mod_reduce_gadget(composer, year, *divisor, n_bits)?;
+        let divisible = maybe_equal(composer, remainder, zero);
+        let divisible_scalar = BlsScalar::from((remainder.scalar == BlsScalar::zero()) as u64);
+        leap = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), leap.var),
+                (*sign, divisible),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: leap.scalar + sign * divisible_scalar,
+        };
+    }
+
+    // One-hot selection of the month, looking up its length
+    let mut selectors_sum: Variable = composer.zero_var();
+    let mut selected_month: Variable = composer.zero_var();
+    let mut days_in_month = zero;
+    for (i, days) in DAYS_IN_MONTH.iter().enumerate() {
+        let number = BlsScalar::from(i as u64 + 1);
+        let selector =
+            AllocatedScalar::allocate(composer, BlsScalar::from((number == month.scalar) as u64));
+        composer.boolean_gate(selector.var);
+
+        selectors_sum = composer.add(
+            (BlsScalar::one(), selectors_sum),
+            (BlsScalar::one(), selector.var),
+            BlsScalar::zero(),
+            None,
+        );
+        selected_month = composer.add(
+            (BlsScalar::one(), selected_month),
+            (number, selector.var),
+            BlsScalar::zero(),
+            None,
+        );
+        days_in_month = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), days_in_month.var),
+                (BlsScalar::from(*days), selector.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: days_in_month.scalar + BlsScalar::from(*days) * selector.scalar,
+        };
+
+        // February has an extra day in leap years
+        if i == 1 {
+            let extra_day = composer.mul(
+                BlsScalar::one(),
+                selector.var,
+                leap.var,
+                BlsScalar::zero(),
+                None,
+            );
+            days_in_month = AllocatedScalar {
+                var: composer.add(
+                    (BlsScalar::one(), days_in_month.var),
+                    (BlsScalar::one(), extra_day),
+                    BlsScalar::zero(),
+                    None,
+                ),
+                scalar: days_in_month.scalar + selector.scalar * leap.scalar,
+            };
+        }
+    }
+    composer.constrain_to_constant(selectors_sum, BlsScalar::one(), None);
+    composer.assert_equal(selected_month, month.var);
+
+    // 1 <= day <= days_in_month
+    let day_minus_one = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), day.var),
+            (BlsScalar::zero(), day.var),
+            -BlsScalar::one(),
+            None,
+        ),
+        scalar: day.scalar - BlsScalar::one(),
+    };
+    range_gadget(composer, day_minus_one, 5)?;
+    let remaining_days = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), days_in_month.var),
+            (-BlsScalar::one(), day.var),
+            BlsScalar::zero(),
+            None,
+        ),
+        scalar: days_in_month.scalar - day.scalar,
+    };
+    range_gadget(composer, remaining_days, 5)
+}
+
+/// Returns `value mod modulus`, constraining
+/// value = quotient * modulus + remainder,
+/// where the remainder is proven to lie in [0, modulus) and the quotient is
+/// range-checked to `n_bits` bits, so `value` is assumed to fit in `n_bits` bits.
+/// Returns `BitLengthOverflow` if `n_bits` plus the bits of the modulus exceed
+/// `MAX_BITS`, since the recomposition could then wrap around the field modulus.
+///
+/// # Panics
+/// If `modulus` is zero.
+pub fn mod_reduce_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    modulus: u64,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    assert!(modulus != 0, "Can't reduce modulo zero");
+    let modulus_bits = (64 - modulus.leading_zeros()) as usize;
+    if n_bits + modulus_bits > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    // Compute the witness assignments for the quotient and the remainder
+    let (quotient, remainder) = scalar_div_rem(&value.scalar, modulus);
+    let quotient = AllocatedScalar::allocate(composer, quotient);
+    let remainder = AllocatedScalar::allocate(composer, BlsScalar::from(remainder));
+
+    // quotient * modulus + remainder = value
+    let recomposed = composer.add(
+        (BlsScalar::from(modulus), quotient.var),
+        (BlsScalar::one(), remainder.var),
+        BlsScalar::zero(),
+        None,
+    );
+    composer.assert_equal(recomposed, value.var);
+    range_gadget(composer, quotient, n_bits)?;
+
+    // 0 <= remainder and modulus - 1 - remainder >= 0
+    range_gadget(composer, remainder, modulus_bits)?;
+    let max_remainder = BlsScalar::from(modulus - 1);
+    let remainder_slack = AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), remainder.var),
+            (BlsScalar::zero(), remainder.var),
+            max_remainder,
+            None,
+        ),
+        scalar: max_remainder - remainder.scalar,
+    };
+    range_gadget(composer, remainder_slack, modulus_bits)?;
+
+    Ok(remainder)
+}
+
+/// Returns 1 if |value - target| <= k and 0 otherwise, where `value` and `target`
+/// are private witnesses and `k` is part of the circuit.
+/// Unlike `approx_equal_gadget`, the proof doesn't fail when the values are
+/// further apart, which allows to count how many values fall near the target.
+/// Both values (and `k`) are assumed to be representable in `n_bits` bits.
+pub fn within_window_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    target: AllocatedScalar,
+    k: u64,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    let abs_diff = abs_diff_gadget(composer, value, target, n_bits)?;
+    let k = AllocatedScalar {
+        var: composer.add_witness_to_circuit_description(BlsScalar::from(k)),
+        scalar: BlsScalar::from(k),
+    };
+
+    is_less_or_equal_gadget(composer, abs_diff, k, n_bits)
+}
+
+/// Constrains the timestamp `now` to lie in the validity window of a credential
+/// issued at `issued_at` and valid for `valid_for` units of time, i.e.
+/// issued_at <= now < issued_at + valid_for
+/// `now` is usually bound to a Public Input set by the verifier.
+/// All the timestamps are assumed to be representable in `n_bits` bits.
+pub fn not_expired_gadget(
+    composer: &mut StandardComposer,
+    issued_at: AllocatedScalar,
+    valid_for: u64,
+    now: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    // now - issued_at >= 0
+    let elapsed = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), now.var),
+            (-BlsScalar::one(), issued_at.var),
+            BlsScalar::zero(),
+            None,
+        ),
+        scalar: now.scalar - issued_at.scalar,
+    };
+    range_gadget(composer, elapsed, n_bits)?;
+
+    // now < issued_at + valid_for
+    let valid_for = BlsScalar::from(valid_for);
+    let expiry = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), issued_at.var),
+            (BlsScalar::zero(), issued_at.var),
+            valid_for,
+            None,
+        ),
+        scalar: issued_at.scalar + valid_for,
+    };
+    less_than_gadget(composer, now, expiry, n_bits)
+}
+
+/// Constrains each of the snapshots to be greater than or equal to the previous one
+/// (see `is_sorted_gadget`), as is the case for the values of a monotone counter.
+/// The snapshots are assumed to be range-checked to `n_bits` bits by the caller.
+/// Returns `NonMonotonicSequence` as soon as a snapshot lower than the previous
+/// one is found, instead of building a circuit which can't be satisfied.
+pub fn monotone_nondecreasing_gadget(
+    composer: &mut StandardComposer,
+    snapshots: &Vec<AllocatedScalar>,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if snapshots
+        .windows(2)
+        .any(|pair| scalar_cmp(&pair[1].scalar, &pair[0].scalar) == Ordering::Less)
+    {
+        return Err(GadgetsError::NonMonotonicSequence);
+    }
+
+    is_sorted_gadget(composer, snapshots, n_bits)
+}
+
+/// Constrains `next` to be the output of a step of the linear congruential
+/// generator with parameters `a`, `c` and `m` applied to `state`, i.e.
+/// next = (a * state + c) mod m
+/// (see `mod_reduce_gadget`), where a * state + c is assumed to fit in `n_bits` bits.
+///
+/// # Panics
+/// If `m` is zero.
+pub fn lcg_step_gadget(
+    composer: &mut StandardComposer,
+    state: AllocatedScalar,
+    a: u64,
+    c: u64,
+    m: u64,
+    next: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    // a * state + c
+    let (a, c) = (BlsScalar::from(a), BlsScalar::from(c));
+    let affine = AllocatedScalar {
+        var: composer.add((a, state.var), (BlsScalar::zero(), state.var), c, None),
+        scalar: a * state.scalar + c,
+    };
+
+    let reduced = mod_reduce_gadget(composer, affine, m, n_bits)?;
+    composer.assert_equal(reduced.var, next.var);
+
+    Ok(())
+}
+
+/// Returns the integer square root of `value`, i.e. r = floor(sqrt(value)), which
+/// is supplied by the prover and constrained to satisfy
+/// r^2 <= value < (r + 1)^2
+/// by range-checking value - r^2 and r^2 + 2r - value to `n_bits` bits, and
+/// `r` itself to half of them. Hence `value` is assumed to fit in `n_bits` bits.
+/// Returns `BitLengthOverflow` if `n_bits + 2` exceeds `MAX_BITS`, since (r + 1)^2
+/// could then wrap around the field modulus.
+pub fn isqrt_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    if n_bits + 2 > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+    let root_bits = n_bits.div_ceil(2);
+
+    // Compute the witness assignment for the root, from its most significant bit
+    let mut root = BlsScalar::zero();
+    for i in (0..root_bits).rev() {
+        let candidate = root + BlsScalar::from(2).pow(&[i as u64, 0, 0, 0]);
+        if scalar_gte(&value.scalar, &candidate.square()) {
+            root = candidate;
+        }
+    }
+    let root = AllocatedScalar::allocate(composer, root);
+    range_gadget(composer, root, root_bits)?;
+
+    // r^2
+    let root_sq = composer.mul(
+        BlsScalar::one(),
+        root.var,
+        root.var,
+        BlsScalar::zero(),
+        None,
+    );
+    let root_sq_scalar = root.scalar.square();
+
+    // value - r^2 >= 0
+    let lower_slack = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), value.var),
+            (-BlsScalar::one(), root_sq),
+            BlsScalar::zero(),
+            None,
+        ),
+        scalar: value.scalar - root_sq_scalar,
+    };
+    range_gadget(composer, lower_slack, n_bits)?;
+
+    // (r + 1)^2 - 1 - value = r^2 + 2r - value >= 0
+    let root_sq_plus_2r = composer.add(
+        (BlsScalar::one(), root_sq),
+        (BlsScalar::from(2), root.var),
+        BlsScalar::zero(),
+        None,
+    );
+    let upper_slack = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), root_sq_plus_2r),
+            (-BlsScalar::one(), value.var),
+            BlsScalar::zero(),
+            None,
+        ),
+        scalar: root_sq_scalar + root.scalar.double() - value.scalar,
+    };
+    range_gadget(composer, upper_slack, n_bits)?;
+
+    Ok(root)
+}
+
+/// Constrains `y` to be the evaluation at `x` of the piecewise-linear function
+/// whose i-th segment is y = slopes[i] * x + intercepts[i], where segment 0 covers
+/// the values below breakpoints[0], segment i the interval
+/// [breakpoints[i - 1], breakpoints[i]) and the last segment everything from the
+/// last breakpoint onwards. All the parameters form part of the circuit.
+/// The segment is selected with flags g_i = (breakpoints[i] <= x), so that
+/// y = f_0(x) + sum_i(g_i * (f_{i+1}(x) - f_i(x)))
+/// `x` and the breakpoints are assumed to be representable in `n_bits` bits.
+/// Returns `LengthMismatch` unless there is one more slope and intercept than
+/// breakpoints, and `NonMonotonicSequence` if the breakpoints aren't strictly increasing.
+pub fn piecewise_linear_gadget(
+    composer: &mut StandardComposer,
+    x: AllocatedScalar,
+    breakpoints: &[u64],
+    slopes: &[u64],
+    intercepts: &[u64],
+    y: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if slopes.len() != breakpoints.len() + 1 || intercepts.len() != slopes.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+    if breakpoints.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err(GadgetsError::NonMonotonicSequence);
+    }
+
+    // f_i(x) = slopes[i] * x + intercepts[i]
+    let segments: Vec<Variable> = slopes
+        .iter()
+        .zip(intercepts.iter())
+        .map(|(slope, intercept)| {
+            composer.add(
+                (BlsScalar::from(*slope), x.var),
+                (BlsScalar::zero(), x.var),
+                BlsScalar::from(*intercept),
+                None,
+            )
+        })
+        .collect();
+
+    let mut accumulator = segments[0];
+    for (i, breakpoint) in breakpoints.iter().enumerate() {
+        let breakpoint = AllocatedScalar {
+            var: composer.add_witness_to_circuit_description(BlsScalar::from(*breakpoint)),
+            scalar: BlsScalar::from(*breakpoint),
+        };
+        let past_breakpoint = is_less_or_equal_gadget(composer, breakpoint, x, n_bits)?;
+
+        // g_i * (f_{i+1}(x) - f_i(x))
+        let delta = composer.add(
+            (BlsScalar::one(), segments[i + 1]),
+            (-BlsScalar::one(), segments[i]),
+            BlsScalar::zero(),
+            None,
+        );
+        let selected_delta = composer.mul(
+            BlsScalar::one(),
+            past_breakpoint.var,
+            delta,
+            BlsScalar::zero(),
+            None,
+        );
+        accumulator = composer.add(
+            (BlsScalar::one(), accumulator),
+            (BlsScalar::one(), selected_delta),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.assert_equal(accumulator, y.var);
+
+    Ok(())
+}
+
+/// Returns the rank of `value` within the public list `sorted_public`, i.e. the
+/// amount of its elements which are strictly lower than `value`. Each element p_i
+/// forms part of the circuit, so its flag is simply computed as
+/// `is_less_or_equal_gadget(p_i + 1, value)` against a constant.
+/// The count doesn't rely on the list being sorted, which only makes the rank
+/// meaningful as a position. `value` and the elements are assumed to be
+/// representable in `n_bits` bits.
+pub fn rank_in_public_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    sorted_public: &[BlsScalar],
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    let mut rank = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for elem in sorted_public.iter() {
+        let elem_plus_one = AllocatedScalar {
+            var: composer.add_witness_to_circuit_description(elem + BlsScalar::one()),
+            scalar: elem + BlsScalar::one(),
+        };
+        let is_lower = is_less_or_equal_gadget(composer, elem_plus_one, value, n_bits)?;
+        rank = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), rank.var),
+                (BlsScalar::one(), is_lower.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: rank.scalar + is_lower.scalar,
+        };
+    }
+
+    Ok(rank)
+}
+
+/// Constrains `value` to be consistent with its residues, i.e.
+/// value mod modulus = residue
+/// for each of the `(modulus, residue)` pairs, using `mod_reduce_gadget`.
+/// `value` is assumed to fit in `n_bits` bits.
+///
+/// # Panics
+/// If any of the moduli is zero.
+pub fn crt_consistency_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    residues: &[(u64, AllocatedScalar)],
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    for (modulus, residue) in residues.iter() {
+        let reduced = mod_reduce_gadget(composer, value, *modulus, n_bits)?;
+        composer.assert_equal(reduced.var, residue.var);
+    }
+
+    Ok(())
+}
+
+/// Decomposes `value` into `n_bits` little-endian bits, constraining each of
+/// them to be a bit and their weighted sum to be equal to `value`.
+pub(crate) fn bit_decomposition(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    n_bits: usize,
+) -> Result<Vec<AllocatedScalar>, GadgetsError> {
+    if n_bits > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+    let bits: Vec<AllocatedScalar> = scalar_to_bits(&value.scalar)[..n_bits]
+        .iter()
+        .map(|bit| AllocatedScalar::allocate(composer, BlsScalar::from(*bit as u64)))
+        .collect();
+    assert_bit_decomposition_gadget(composer, value, &bits)?;
+
+    Ok(bits)
+}
+
+/// Decomposes a fixed-point number encoded as `integer * 2^frac_bits + fraction`
+/// into its integer and fractional parts, which are returned in that order.
+/// The integer part is constrained to `int_bits` bits and the fractional one to
+/// `frac_bits` bits, so the proof fails if `value` doesn't fit in `int_bits + frac_bits` bits.
+pub fn fixed_point_decompose_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    int_bits: usize,
+    frac_bits: usize,
+) -> Result<(AllocatedScalar, AllocatedScalar), GadgetsError> {
+    if int_bits + frac_bits > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    // Compute the witness assignments for both parts
+    let value_bits = scalar_to_bits(&value.scalar);
+    let mut integer = BlsScalar::zero();
+    let mut fraction = BlsScalar::zero();
+    for (power, bit) in value_bits.iter().enumerate() {
+        let bit = BlsScalar::from(*bit as u64);
+        if power < frac_bits {
+            fraction += BlsScalar::from(2).pow(&[power as u64, 0, 0, 0]) * bit;
+        } else {
+            integer += BlsScalar::from(2).pow(&[(power - frac_bits) as u64, 0, 0, 0]) * bit;
+        }
+    }
+    let integer = AllocatedScalar::allocate(composer, integer);
+    let fraction = AllocatedScalar::allocate(composer, fraction);
+
+    range_gadget(composer, integer, int_bits)?;
+    range_gadget(composer, fraction, frac_bits)?;
+
+    // integer * 2^frac_bits + fraction = value
+    let recomposed = composer.add(
+        (
+            BlsScalar::from(2).pow(&[frac_bits as u64, 0, 0, 0]),
+            integer.var,
+        ),
+        (BlsScalar::one(), fraction.var),
+        BlsScalar::zero(),
+        None,
+    );
+    composer.assert_equal(recomposed, value.var);
+
+    Ok((integer, fraction))
+}
+
+/// Constrains `packed` to be the concatenation of the bit-fields `high` and `low`:
+/// packed = high * 2^low_bits + low
+/// where `low` is range-checked to `low_bits` bits, so it can't spill into `high`.
+/// NOTE that `high` is not range-checked, so the caller must bound it if the
+/// packed value shouldn't wrap around the field modulus.
+/// Returns `BitLengthOverflow` if `low_bits` exceeds `MAX_BITS`.
+pub fn pack_fields_gadget(
+    composer: &mut StandardComposer,
+    high: AllocatedScalar,
+    low: AllocatedScalar,
+    low_bits: usize,
+    packed: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    range_gadget(composer, low, low_bits)?;
+
+    // high * 2^low_bits + low = packed
+    let recomposed = composer.add(
+        (
+            BlsScalar::from(2).pow(&[low_bits as u64, 0, 0, 0]),
+            high.var,
+        ),
+        (BlsScalar::one(), low.var),
+        BlsScalar::zero(),
+        None,
+    );
+    composer.assert_equal(recomposed, packed.var);
+
+    Ok(())
+}
+
+/// Decomposes `packed` into consecutive bit-fields of the given `widths`, starting
+/// from the least significant bits, which are returned in that order.
+/// Each field is range-checked to its width, and their recomposition
+/// sum(field_i * 2^(width_0 + ... + width_(i-1)))
+/// is constrained to be equal to `packed`, so the proof fails if `packed` doesn't
+/// fit in the total width.
+/// Returns `BitLengthOverflow` if the total width exceeds `MAX_BITS`.
+pub fn unpack_fields_gadget(
+    composer: &mut StandardComposer,
+    packed: AllocatedScalar,
+    widths: &[u32],
+) -> Result<Vec<AllocatedScalar>, GadgetsError> {
+    let total_width: usize = widths.iter().map(|width| *width as usize).sum();
+    if total_width > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    let packed_bits = scalar_to_bits(&packed.scalar);
+    let mut fields: Vec<AllocatedScalar> = Vec::with_capacity(widths.len());
+    let mut accumulator: Variable = composer.zero_var();
+    let mut offset = 0usize;
+    for width in widths.iter().map(|width| *width as usize) {
+        // Compute the witness assignment for the field
+        let field = packed_bits[offset..offset + width].iter().enumerate().fold(
+            BlsScalar::zero(),
+            |acc, (power, bit)| {
+                acc + BlsScalar::from(2).pow(&[power as u64, 0, 0, 0])
+                    * BlsScalar::from(*bit as u64)
+            },
+        );
+        let field = AllocatedScalar::allocate(composer, field);
+        range_gadget(composer, field, width)?;
+
+        accumulator = composer.add(
+            (BlsScalar::from(2).pow(&[offset as u64, 0, 0, 0]), field.var),
+            (BlsScalar::one(), accumulator),
+            BlsScalar::zero(),
+            None,
+        );
+        fields.push(field);
+        offset += width;
+    }
+    composer.assert_equal(accumulator, packed.var);
+
+    Ok(fields)
+}
+
+/// Returns min(a + b, max), where `a` and `b` are private witnesses and the
+/// saturation bound `max` is part of the circuit.
+/// The true sum is compared against `max`, and the result is selected as
+/// s * (a + b - max) + max, where s = 1 if a + b <= max and 0 otherwise.
+/// Both values (and `max`) are assumed to be representable in `n_bits` bits.
+pub fn saturating_add_gadget(
+    composer: &mut StandardComposer,
+    a: AllocatedScalar,
+    b: AllocatedScalar,
+    max: u64,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    let sum = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), a.var),
+            (BlsScalar::one(), b.var),
+            BlsScalar::zero(),
+            None,
+        ),
+        scalar: a.scalar + b.scalar,
+    };
+    let max = BlsScalar::from(max);
+    let max_var = AllocatedScalar {
+        var: composer.add_witness_to_circuit_description(max),
+        scalar: max,
+    };
+
+    // The sum takes up to `n_bits + 1` bits
+    let below_max = is_less_or_equal_gadget(composer, sum, max_var, n_bits + 1)?;
+
+    Ok(select_or_constant(composer, below_max, sum, max))
+}
+
+/// Returns max(lo, min(value, hi)), where `value` is a private witness and the
+/// bounds are part of the circuit.
+/// The value is first compared against `hi` and the minimum selected, which is
+/// then compared against `lo` to select the maximum (see `saturating_add_gadget`).
+/// The value (and both bounds) are assumed to be representable in `n_bits` bits.
+/// Returns `NonMonotonicSequence` if `lo` is greater than `hi`.
+pub fn clamp_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    lo: u64,
+    hi: u64,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    if lo > hi {
+        return Err(GadgetsError::NonMonotonicSequence);
+    }
+    let lo = BlsScalar::from(lo);
+    let hi = BlsScalar::from(hi);
+    let lo_var = AllocatedScalar {
+        var: composer.add_witness_to_circuit_description(lo),
+        scalar: lo,
+    };
+    let hi_var = AllocatedScalar {
+        var: composer.add_witness_to_circuit_description(hi),
+        scalar: hi,
+    };
+
+    // min(value, hi)
+    let below_hi = is_less_or_equal_gadget(composer, value, hi_var, n_bits)?;
+    let upper_clamped = select_or_constant(composer, below_hi, value, hi);
+
+    // max(lo, min(value, hi))
+    let above_lo = is_less_or_equal_gadget(composer, lo_var, upper_clamped, n_bits)?;
+
+    Ok(select_or_constant(composer, above_lo, upper_clamped, lo))
+}
+
+/// Constrain `ops` to be a valid trace of stack operations ending at `final_depth`.
+/// Each operation is a pair `(is_push, value)`, where `is_push` is constrained to be
+/// a bit. Starting from an empty stack, the depth after each operation is
+/// d_i = d_(i-1) + 2 * is_push - 1
+/// and it's range-checked to `n_bits` bits, so that popping from an empty stack
+/// (which wraps the depth around the field modulus) makes the proof fail.
+/// The values aren't constrained, since only the depth is tracked.
+pub fn stack_consistency_gadget(
+    composer: &mut StandardComposer,
+    ops: &Vec<(AllocatedScalar, AllocatedScalar)>,
+    final_depth: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let mut depth = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for (is_push, _) in ops.iter() {
+        composer.boolean_gate(is_push.var);
+        depth = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), depth.var),
+                (BlsScalar::from(2), is_push.var),
+                -BlsScalar::one(),
+                None,
+            ),
+            scalar: depth.scalar + is_push.scalar.double() - BlsScalar::one(),
+        };
+        range_gadget(composer, depth, n_bits)?;
+    }
+    composer.assert_equal(depth.var, final_depth.var);
+
+    Ok(())
+}
+
+/// Constrain `probs` to be a discrete probability distribution in fixed point,
+/// where `scale` represents 1.0, i.e. each probability lies in [0, scale] and
+/// they add up to exactly `scale`.
+/// Each probability is range-checked to `n_bits` bits, as well as scale - prob,
+/// so `scale` is assumed to be representable in `n_bits` bits.
+pub fn probability_distribution_gadget(
+    composer: &mut StandardComposer,
+    probs: &Vec<AllocatedScalar>,
+    scale: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let scale = BlsScalar::from(scale);
+    let mut sum: Variable = composer.zero_var();
+    for prob in probs.iter() {
+        range_gadget(composer, *prob, n_bits)?;
+        let slack = AllocatedScalar {
+            var: composer.add(
+                (-BlsScalar::one(), prob.var),
+                (BlsScalar::zero(), prob.var),
+                scale,
+                None,
+            ),
+            scalar: scale - prob.scalar,
+        };
+        range_gadget(composer, slack, n_bits)?;
+
+        sum = composer.add(
+            (BlsScalar::one(), sum),
+            (BlsScalar::one(), prob.var),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.constrain_to_constant(sum, scale, None);
+
+    Ok(())
+}
+
+/// Constrain the variance of `values` around `mean` to be bounded by `var_bound`
+/// without any division, i.e.
+/// sum((v_i - mean)^2) <= var_bound * n
+/// where n is the number of values. The squared differences are added up and
+/// var_bound * n minus their sum is range-checked to 2 * n_bits + log2(n) bits.
+/// The values and the mean are assumed to be representable in `n_bits` bits, and
+/// `var_bound` in `2 * n_bits` bits.
+/// Returns `BitLengthOverflow` if the sum could wrap around the field modulus.
+pub fn within_variance_bound_gadget(
+    composer: &mut StandardComposer,
+    values: &Vec<AllocatedScalar>,
+    mean: AllocatedScalar,
+    var_bound: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let sum_bits = 2 * n_bits + (usize::BITS - values.len().leading_zeros()) as usize;
+    if sum_bits > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    let mut sum = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for value in values.iter() {
+        // (v_i - mean)^2
+        let diff = composer.add(
+            (BlsScalar::one(), value.var),
+            (-BlsScalar::one(), mean.var),
+            BlsScalar::zero(),
+            None,
+        );
+        let square = composer.mul(BlsScalar::one(), diff, diff, BlsScalar::zero(), None);
+        sum = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), sum.var),
+                (BlsScalar::one(), square),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: sum.scalar + (value.scalar - mean.scalar).square(),
+        };
+    }
+
+    // var_bound * n - sum >= 0
+    let bound = BlsScalar::from(var_bound) * BlsScalar::from(values.len() as u64);
+    let slack = AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), sum.var),
+            (BlsScalar::zero(), sum.var),
+            bound,
+            None,
+        ),
+        scalar: bound - sum.scalar,
+    };
+    range_gadget(composer, slack, sum_bits)
+}
+
+/// Constrain the state transition of a token bucket rate limiter, i.e.
+/// new_tokens = min(prev_tokens + refill, max) - cost
+/// where the balance before paying the cost is computed with `saturating_add_gadget`.
+/// The new balance is range-checked to `n_bits` bits, which proves that the
+/// balance before paying was at least `cost`.
+/// The balances (and the parameters) are assumed to be representable in `n_bits` bits.
+pub fn token_bucket_gadget(
+    composer: &mut StandardComposer,
+    prev_tokens: AllocatedScalar,
+    refill: u64,
+    cost: u64,
+    max: u64,
+    new_tokens: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let refill = BlsScalar::from(refill);
+    let refill = AllocatedScalar {
+        var: composer.add_witness_to_circuit_description(refill),
+        scalar: refill,
+    };
+    let balance = saturating_add_gadget(composer, prev_tokens, refill, max, n_bits)?;
+
+    // new_tokens = balance - cost
+    let cost = BlsScalar::from(cost);
+    let remaining = composer.add(
+        (BlsScalar::one(), balance.var),
+        (BlsScalar::zero(), balance.var),
+        -cost,
+        None,
+    );
+    composer.assert_equal(remaining, new_tokens.var);
+
+    range_gadget(composer, new_tokens, n_bits)
+}
+
+/// Constrains the `(start, end)` intervals to be well-formed and non-overlapping,
+/// given that they're sorted by their start, i.e.
+/// start_1 <= end_1 <= start_2 <= end_2 <= ... <= start_n <= end_n
+/// which is proven by flattening the intervals and checking the resulting
+/// vector to be sorted (see `is_sorted_gadget`). Hence an interval can start
+/// at the same point the previous one ends.
+/// NOTE that the bounds are assumed to be range-checked to `n_bits` bits by the caller.
+pub fn non_overlapping_intervals_gadget(
+    composer: &mut StandardComposer,
+    intervals: &Vec<(AllocatedScalar, AllocatedScalar)>,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let mut bounds: Vec<AllocatedScalar> = Vec::with_capacity(2 * intervals.len());
+    for (start, end) in intervals.iter() {
+        bounds.push(*start);
+        bounds.push(*end);
+    }
+    is_sorted_gadget(composer, &bounds, n_bits)
+}
+
+/// Constrains the vector to be sorted in non-decreasing order with every gap
+/// between consecutive elements bounded by `max_gap`, i.e.
+/// 0 <= v_(i+1) - v_i <= max_gap
+/// by proving that both v_(i+1) - v_i and max_gap - (v_(i+1) - v_i) lie in
+/// [0, 2^n_bits) (see `is_sorted_gadget`).
+/// Since every gap fits in `n_bits` bits, a `max_gap` of 2^n_bits or above is
+/// clamped to 2^n_bits - 1, so the slack always fits in `n_bits` bits as well.
+/// NOTE that the elements are assumed to be range-checked to `n_bits` bits by
+/// the caller, otherwise the differences could wrap around the field modulus.
+pub fn bounded_gap_gadget(
+    composer: &mut StandardComposer,
+    sorted: &Vec<AllocatedScalar>,
+    max_gap: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let max_gap = if n_bits < 64 {
+        max_gap.min((1 << n_bits) - 1)
+    } else {
+        max_gap
+    };
+    let max_gap = BlsScalar::from(max_gap);
+    for pair in sorted.windows(2) {
+        let gap = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), pair[1].var),
+                (-BlsScalar::one(), pair[0].var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: pair[1].scalar - pair[0].scalar,
+        };
+        range_gadget(composer, gap, n_bits)?;
+
+        let slack = AllocatedScalar {
+            var: composer.add(
+                (-BlsScalar::one(), gap.var),
+                (BlsScalar::zero(), gap.var),
+                max_gap,
+                None,
+            ),
+            scalar: max_gap - gap.scalar,
+        };
+        range_gadget(composer, slack, n_bits)?;
+    }
+
+    Ok(())
+}
+
+/// Constrain `result` to be base^exp mod modulus, where the exponent is given by its
+/// little-endian bits `exp_bits`, each of which is constrained to be a bit.
+/// It is computed by right-to-left square-and-multiply: starting from acc = 1 and
+/// p = base mod modulus, for each bit b_i
+/// acc = acc * (b_i ? p : 1) mod modulus and p = p^2 mod modulus
+/// where every product is reduced with `mod_reduce_gadget`.
+/// The base is assumed to be representable in `n_bits` bits.
+/// Returns `BitLengthOverflow` if `n_bits` plus the bits of the modulus exceed `MAX_BITS`.
+///
+/// # Panics
+/// If `modulus` is zero.
+pub fn mod_exp_gadget(
+    composer: &mut StandardComposer,
+    base: AllocatedScalar,
+    exp_bits: &Vec<AllocatedScalar>,
+    modulus: u64,
+    result: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    // The product of two reduced values fits in twice the bits of the modulus
+    let product_bits = 2 * (64 - modulus.leading_zeros()) as usize;
+
+    let mut power = mod_reduce_gadget(composer, base, modulus, n_bits)?;
+    let one = BlsScalar::one();
+    let mut accumulator = AllocatedScalar {
+        var: composer.add_witness_to_circuit_description(one),
+        scalar: one,
+    };
+    for (i, bit) in exp_bits.iter().enumerate() {
+        composer.boolean_gate(bit.var);
+
+        // acc * (b_i ? p : 1) mod modulus
+        let factor = AllocatedScalar {
+            var: conditionally_select_one(composer, power.var, bit.var),
+            scalar: bit.scalar * power.scalar + one - bit.scalar,
+        };
+        let product = AllocatedScalar {
+            var: composer.mul(
+                BlsScalar::one(),
+                accumulator.var,
+                factor.var,
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: accumulator.scalar * factor.scalar,
+        };
+        accumulator = mod_reduce_gadget(composer, product, modulus, product_bits)?;
+
+        // The power isn't needed after the last bit
+        if i + 1 < exp_bits.len() {
+            let square = AllocatedScalar {
+                var: composer.mul(
+                    BlsScalar::one(),
+                    power.var,
+                    power.var,
+                    BlsScalar::zero(),
+                    None,
+                ),
+                scalar: power.scalar.square(),
+            };
+            power = mod_reduce_gadget(composer, square, modulus, product_bits)?;
+        }
+    }
+    composer.assert_equal(accumulator.var, result.var);
+
+    Ok(())
+}
+
+/// Constrains `value` to be a valid fixed-point percentage, i.e. 0 <= value <= scale,
+/// where `scale` represents 100% (see `DEFAULT_PERCENTAGE_SCALE`).
+/// It is done by proving that both `value` and scale - value lie in [0, 2^k),
+/// where `k` is the amount of bits of the scale, so the checks never exceed 64 bits
+/// and can't wrap around the field modulus.
+pub fn percentage_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    scale: u64,
+) -> Result<(), GadgetsError> {
+    let n_bits = (64 - scale.leading_zeros()) as usize;
+    range_gadget(composer, value, n_bits)?;
+
+    // Compute scale - value in the circuit
+    let scale = BlsScalar::from(scale);
+    let slack = AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), value.var),
+            (BlsScalar::zero(), value.var),
+            scale,
+            None,
+        ),
+        scalar: scale - value.scalar,
+    };
+
+    range_gadget(composer, slack, n_bits)
+}
+
+// Constrains `digit` to be a base-10 digit, i.e.
+// d * (d - 1) * ... * (d - 9) = 0
+fn digit_constraint(composer: &mut StandardComposer, digit: AllocatedScalar) {
+    let mut product = digit.var;
+    for i in 1..10u64 {
+        let diff = composer.add(
+            (BlsScalar::one(), digit.var),
+            (BlsScalar::zero(), digit.var),
+            -BlsScalar::from(i),
+            None,
+        );
+        product = composer.mul(BlsScalar::one(), product, diff, BlsScalar::zero(), None);
+    }
+    composer.constrain_to_constant(product, BlsScalar::zero(), None);
+}
+
+// Returns `value` if `flag` is 1 and the `constant` if it is 0, computed as
+// flag * (value - constant) + constant
+// The flag is assumed to be constrained to be a bit by the caller.
+fn select_or_constant(
+    composer: &mut StandardComposer,
+    flag: AllocatedScalar,
+    value: AllocatedScalar,
+    constant: BlsScalar,
+) -> AllocatedScalar {
+    let diff = composer.add(
+        (BlsScalar::one(), value.var),
+        (BlsScalar::zero(), value.var),
+        -constant,
+        None,
+    );
+    AllocatedScalar {
+        var: composer.mul(BlsScalar::one(), flag.var, diff, constant, None),
+        scalar: flag.scalar * (value.scalar - constant) + constant,
+    }
+}
+
 // Decompose a `BlsScalar` into its 256-bit representation.
 fn scalar_to_bits(scalar: &BlsScalar) -> [u8; 256] {
     let mut res = [0u8; 256];
@@ -169,6 +1642,51 @@ fn scalar_to_bits(scalar: &BlsScalar) -> [u8; 256] {
     res
 }
 
+// Computes the `n` least significant base-10 digits of a `BlsScalar`,
+// least significant first.
+fn scalar_to_digits(scalar: &BlsScalar, n: usize) -> Vec<u8> {
+    let mut bytes = scalar.to_bytes();
+    let mut digits = Vec::with_capacity(n);
+    for _ in 0..n {
+        // Long division of the little-endian bytes by 10
+        let mut remainder = 0u16;
+        for byte in bytes.iter_mut().rev() {
+            let current = (remainder << 8) | *byte as u16;
+            *byte = (current / 10) as u8;
+            remainder = current % 10;
+        }
+        digits.push(remainder as u8);
+    }
+    digits
+}
+
+// Computes the quotient and the remainder of the integer division of a
+// `BlsScalar` by `divisor`.
+fn scalar_div_rem(scalar: &BlsScalar, divisor: u64) -> (BlsScalar, u64) {
+    let mut bytes = scalar.to_bytes();
+    // Long division of the little-endian bytes by the divisor
+    let mut remainder = 0u128;
+    for byte in bytes.iter_mut().rev() {
+        let current = (remainder << 8) | *byte as u128;
+        *byte = (current / divisor as u128) as u8;
+        remainder = current % divisor as u128;
+    }
+    // The quotient is lower than the scalar, hence it's canonical
+    let quotient = BlsScalar::from_bytes(&bytes).unwrap_or(BlsScalar::zero());
+    (quotient, remainder as u64)
+}
+
+// Compares two `BlsScalar`s interpreted as integers.
+pub(crate) fn scalar_cmp(a: &BlsScalar, b: &BlsScalar) -> Ordering {
+    let (a, b) = (a.to_bytes(), b.to_bytes());
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+// Returns whether `a >= b` when both are interpreted as integers.
+pub(crate) fn scalar_gte(a: &BlsScalar, b: &BlsScalar) -> bool {
+    scalar_cmp(a, b) != Ordering::Less
+}
+
 // Count the minimum amount of bits necessary to represent a `BlsScalar`.
 fn bits_count(mut scalar: BlsScalar) -> u64 {
     scalar = scalar.reduce();
@@ -9,7 +9,9 @@
 //! This module actually contains conditional selection implementations as
 //! well as equalty-checking gadgets.
 use super::AllocatedScalar;
+use crate::range::{range_gadget, scalar_gte, MAX_BITS};
 use crate::Error as GadgetsError;
+use alloc::vec::Vec;
 use dusk_plonk::prelude::*;
 
 /// Conditionally selects the value provided or a zero instead.
@@ -96,6 +98,23 @@ pub fn is_non_zero(
     Ok(())
 }
 
+/// Constrains `a != b`, by proving that their difference is non-zero
+/// (see `is_non_zero`).
+/// Returns `NonExistingInverse` if both values are equal.
+pub fn assert_not_equal(
+    composer: &mut StandardComposer,
+    a: AllocatedScalar,
+    b: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    let diff = composer.add(
+        (BlsScalar::one(), a.var),
+        (-BlsScalar::one(), b.var),
+        BlsScalar::zero(),
+        None,
+    );
+    is_non_zero(composer, diff, a.scalar - b.scalar)
+}
+
 /// Returns 1 if a = b and zero otherwise.
 ///
 /// # NOTE
@@ -138,3 +157,315 @@ pub fn maybe_equal(
     }
     y
 }
+
+/// Returns both square roots `(x, -x)` of `value`, constraining
+/// x * x = value and x + (-x) = 0,
+/// so that the negated root also squares to `value`.
+/// Returns `NonExistingSquareRoot` if `value` is not a square inside of the field.
+pub fn sqrt_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+) -> Result<(AllocatedScalar, AllocatedScalar), GadgetsError> {
+    // Compute the square root of `value`.
+    let sqrt = value.scalar.sqrt();
+    let root = if sqrt.is_some().unwrap_u8() == 1u8 {
+        // Safe to unwrap here.
+        AllocatedScalar::allocate(composer, sqrt.unwrap())
+    } else {
+        return Err(GadgetsError::NonExistingSquareRoot);
+    };
+    let neg_root = AllocatedScalar::allocate(composer, -root.scalar);
+
+    // root * root - value = 0
+    composer.poly_gate(
+        root.var,
+        root.var,
+        value.var,
+        BlsScalar::one(),
+        BlsScalar::zero(),
+        BlsScalar::zero(),
+        -BlsScalar::one(),
+        BlsScalar::zero(),
+        None,
+    );
+
+    // root + neg_root = 0
+    let sum = composer.add(
+        (BlsScalar::one(), root.var),
+        (BlsScalar::one(), neg_root.var),
+        BlsScalar::zero(),
+        None,
+    );
+    composer.constrain_to_constant(sum, BlsScalar::zero(), None);
+
+    Ok((root, neg_root))
+}
+
+/// Returns the reciprocal `1/x` of the provided value, constraining
+/// x * recip = 1,
+/// which also proves that `x` is non-zero.
+/// Returns `NonExistingInverse` if `x` is zero.
+pub fn reciprocal_gadget(
+    composer: &mut StandardComposer,
+    x: AllocatedScalar,
+) -> Result<AllocatedScalar, GadgetsError> {
+    // Compute the inverse of `x`.
+    let inverse = x.scalar.invert();
+    let recip = if inverse.is_some().unwrap_u8() == 1u8 {
+        // Safe to unwrap here.
+        AllocatedScalar::allocate(composer, inverse.unwrap())
+    } else {
+        return Err(GadgetsError::NonExistingInverse);
+    };
+
+    // x * recip = 1
+    let one = composer.add_witness_to_circuit_description(BlsScalar::one());
+    composer.poly_gate(
+        x.var,
+        recip.var,
+        one,
+        BlsScalar::one(),
+        BlsScalar::zero(),
+        BlsScalar::zero(),
+        -BlsScalar::one(),
+        BlsScalar::zero(),
+        None,
+    );
+
+    Ok(recip)
+}
+
+/// Constrains `next` to be the successor of `prev`, i.e. next = prev + 1,
+/// using a single linear gate.
+pub fn is_successor_gadget(
+    composer: &mut StandardComposer,
+    prev: AllocatedScalar,
+    next: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    // prev - next + 1 = 0
+    let zero = composer.zero_var();
+    composer.poly_gate(
+        prev.var,
+        next.var,
+        zero,
+        BlsScalar::zero(),
+        BlsScalar::one(),
+        -BlsScalar::one(),
+        BlsScalar::zero(),
+        BlsScalar::one(),
+        None,
+    );
+
+    Ok(())
+}
+
+/// Constrains `a` and `b` to be coprime, by checking that the Bézout coefficients
+/// `s_hint` and `t_hint` supplied by the prover satisfy
+/// a * s + b * t = 1
+/// over the integers. Both `a` and `b` are range-checked to `n_bits` bits, and each
+/// coefficient is split into a sign bit and a magnitude range-checked to `n_bits` bits,
+/// which always suffices since |s| <= b and |t| <= a for the minimal coefficients.
+/// Hence neither side of the identity can wrap around the field modulus.
+/// Returns `BitLengthOverflow` if `2 * n_bits + 1` exceeds `MAX_BITS`, since the
+/// integer products could then wrap around it.
+pub fn coprime_gadget(
+    composer: &mut StandardComposer,
+    a: AllocatedScalar,
+    b: AllocatedScalar,
+    s_hint: AllocatedScalar,
+    t_hint: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if 2 * n_bits + 1 > MAX_BITS {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    range_gadget(composer, a, n_bits)?;
+    range_gadget(composer, b, n_bits)?;
+    signed_range_gadget(composer, s_hint, n_bits)?;
+    signed_range_gadget(composer, t_hint, n_bits)?;
+
+    // a * s
+    let a_s = composer.mul(BlsScalar::one(), a.var, s_hint.var, BlsScalar::zero(), None);
+    // b * t + a * s - 1 = 0
+    composer.poly_gate(
+        b.var,
+        t_hint.var,
+        a_s,
+        BlsScalar::one(),
+        BlsScalar::zero(),
+        BlsScalar::zero(),
+        BlsScalar::one(),
+        -BlsScalar::one(),
+        None,
+    );
+
+    Ok(())
+}
+
+/// Constrains the point `(x, y)` to lie on the line y = m * x + b, where all of
+/// them are private witnesses, using one multiplication gate and one addition gate.
+pub fn on_line_gadget(
+    composer: &mut StandardComposer,
+    x: AllocatedScalar,
+    y: AllocatedScalar,
+    m: AllocatedScalar,
+    b: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    // m * x
+    let m_x = composer.mul(BlsScalar::one(), m.var, x.var, BlsScalar::zero(), None);
+    // m * x + b - y = 0
+    composer.poly_gate(
+        m_x,
+        b.var,
+        y.var,
+        BlsScalar::zero(),
+        BlsScalar::one(),
+        BlsScalar::one(),
+        -BlsScalar::one(),
+        BlsScalar::zero(),
+        None,
+    );
+
+    Ok(())
+}
+
+/// Returns the evaluation at `x` of the polynomial with coefficients `coeffs`,
+/// given from the lowest degree to the highest one, using Horner's method:
+/// c_0 + x * (c_1 + x * (c_2 + ... + x * c_n))
+/// The empty list of coefficients is treated as the zero polynomial.
+pub fn poly_eval_gadget(
+    composer: &mut StandardComposer,
+    coeffs: &Vec<AllocatedScalar>,
+    x: AllocatedScalar,
+) -> AllocatedScalar {
+    let mut accumulator = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for coeff in coeffs.iter().rev() {
+        // accumulator * x + coeff
+        let product = composer.mul(
+            BlsScalar::one(),
+            accumulator.var,
+            x.var,
+            BlsScalar::zero(),
+            None,
+        );
+        accumulator = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), product),
+                (BlsScalar::one(), coeff.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: accumulator.scalar * x.scalar + coeff.scalar,
+        };
+    }
+
+    accumulator
+}
+
+/// Constrain the polynomial with coefficients `coeffs` (see `poly_eval_gadget`)
+/// to pass through each of the `(x, y)` points, i.e. poly(x) = y for all of them.
+pub fn interpolation_consistency_gadget(
+    composer: &mut StandardComposer,
+    coeffs: &Vec<AllocatedScalar>,
+    points: &Vec<(AllocatedScalar, AllocatedScalar)>,
+) -> Result<(), GadgetsError> {
+    for (x, y) in points.iter() {
+        let evaluation = poly_eval_gadget(composer, coeffs, *x);
+        composer.assert_equal(evaluation.var, y.var);
+    }
+
+    Ok(())
+}
+
+/// Constrains `share` to be a valid Shamir secret share at the point `x`, i.e.
+/// share = poly(x) for the secret-sharing polynomial with coefficients `coeffs`,
+/// where `coeffs[0]` is the secret (see `poly_eval_gadget`).
+pub fn shamir_share_valid_gadget(
+    composer: &mut StandardComposer,
+    coeffs: &Vec<AllocatedScalar>,
+    x: AllocatedScalar,
+    share: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    let evaluation = poly_eval_gadget(composer, coeffs, x);
+    composer.assert_equal(evaluation.var, share.var);
+
+    Ok(())
+}
+
+/// Constrains `output` to be the number-theoretic transform of `input` over the
+/// public `roots`, i.e. output_k = poly(roots[k]) for the polynomial with
+/// coefficients `input` (see `poly_eval_gadget`). The roots form part of the circuit.
+/// NOTE that every root requires a full Horner evaluation, so this costs
+/// O(n^2) gates instead of the O(n log n) of an FFT.
+/// Returns `LengthMismatch` if there isn't one output per root.
+pub fn ntt_consistency_gadget(
+    composer: &mut StandardComposer,
+    input: &Vec<AllocatedScalar>,
+    output: &Vec<AllocatedScalar>,
+    roots: &[BlsScalar],
+) -> Result<(), GadgetsError> {
+    if output.len() != roots.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    for (root, out) in roots.iter().zip(output.iter()) {
+        let root = AllocatedScalar {
+            var: composer.add_witness_to_circuit_description(*root),
+            scalar: *root,
+        };
+        let evaluation = poly_eval_gadget(composer, input, root);
+        composer.assert_equal(evaluation.var, out.var);
+    }
+
+    Ok(())
+}
+
+/// Constrain `root` to be a root of the polynomial with coefficients `coeffs`
+/// (see `poly_eval_gadget`), i.e. poly(root) = 0
+pub fn has_root_gadget(
+    composer: &mut StandardComposer,
+    coeffs: &Vec<AllocatedScalar>,
+    root: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    let evaluation = poly_eval_gadget(composer, coeffs, root);
+    composer.constrain_to_constant(evaluation.var, BlsScalar::zero(), None);
+
+    Ok(())
+}
+
+// Constrains `x` to lie in the range (-2^n_bits, 2^n_bits), by splitting it into
+// a sign bit `s` and a magnitude `m` s.t. x = (1 - 2s) * m, with `m` range-checked.
+fn signed_range_gadget(
+    composer: &mut StandardComposer,
+    x: AllocatedScalar,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    // Compute the witness assignments for the sign and the magnitude
+    let (sign, magnitude) = if scalar_gte(&-x.scalar, &x.scalar) {
+        (BlsScalar::zero(), x.scalar)
+    } else {
+        (BlsScalar::one(), -x.scalar)
+    };
+    let sign = AllocatedScalar::allocate(composer, sign);
+    composer.boolean_gate(sign.var);
+    let magnitude = AllocatedScalar::allocate(composer, magnitude);
+
+    // -2 * s * m + m - x = 0
+    composer.poly_gate(
+        sign.var,
+        magnitude.var,
+        x.var,
+        -BlsScalar::from(2),
+        BlsScalar::zero(),
+        BlsScalar::one(),
+        -BlsScalar::one(),
+        BlsScalar::zero(),
+        None,
+    );
+
+    range_gadget(composer, magnitude, n_bits)
+}
@@ -10,8 +10,18 @@
 //! set non-membership and set uniqueness
 use super::AllocatedScalar;
 use crate::bit::bit_gadget;
+use crate::matrix::{
+    apply_permutation_gadget, permutation_matrix_constraints, permutation_matrix_witness,
+};
+use crate::range::{
+    approx_equal_gadget, is_sorted_gadget, less_than_gadget, mod_reduce_gadget, range_gadget,
+    scalar_cmp,
+};
+use crate::scalar::{assert_not_equal, is_non_zero, maybe_equal};
 use crate::Error as GadgetsError;
+use alloc::vec;
 use alloc::vec::Vec;
+use core::cmp::Ordering;
 use dusk_plonk::prelude::*;
 
 /// Provided a `Vec<BlsScalar>`, constraint `value` to not be in that vector
@@ -125,6 +135,126 @@ pub fn vector_sum_gadget(
     Ok(())
 }
 
+/// Operation used to combine the elements in `reduce_tree`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReduceOp {
+    /// Addition, whose identity element is zero.
+    Add,
+    /// Multiplication, whose identity element is one.
+    Mul,
+}
+
+/// Combines all of the variables with the provided operation in a balanced
+/// binary tree, so the depth of the resulting computation is log(n) instead of
+/// the n of a left-associative chain.
+/// An empty vector reduces to the identity element of the operation.
+pub fn reduce_tree(composer: &mut StandardComposer, vars: Vec<Variable>, op: ReduceOp) -> Variable {
+    reduce_tree_with_depth(composer, vars, op).0
+}
+
+// Same as `reduce_tree`, also returning the depth of the tree.
+fn reduce_tree_with_depth(
+    composer: &mut StandardComposer,
+    mut vars: Vec<Variable>,
+    op: ReduceOp,
+) -> (Variable, usize) {
+    if vars.is_empty() {
+        let identity = match op {
+            ReduceOp::Add => composer.zero_var(),
+            ReduceOp::Mul => composer.add_witness_to_circuit_description(BlsScalar::one()),
+        };
+        return (identity, 0);
+    }
+
+    let mut depth = 0;
+    while vars.len() > 1 {
+        // Combine the elements pairwise, carrying the odd one to the next level
+        vars = vars
+            .chunks(2)
+            .map(|pair| match (pair, op) {
+                ([a, b], ReduceOp::Add) => composer.add(
+                    (BlsScalar::one(), *a),
+                    (BlsScalar::one(), *b),
+                    BlsScalar::zero(),
+                    None,
+                ),
+                ([a, b], ReduceOp::Mul) => {
+                    composer.mul(BlsScalar::one(), *a, *b, BlsScalar::zero(), None)
+                }
+                _ => pair[0],
+            })
+            .collect();
+        depth += 1;
+    }
+
+    (vars[0], depth)
+}
+
+/// Same as `vector_sum_gadget`, but the elements are added up in a balanced
+/// tree (see `reduce_tree`) instead of a linear chain.
+pub fn vector_sum_tree_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    expected_sum: u64,
+) -> Result<(), GadgetsError> {
+    let vars = vector.iter().map(|elem| elem.var).collect();
+    let sum = reduce_tree(composer, vars, ReduceOp::Add);
+
+    // The expected sum is not part of the circuit,
+    // hence we place it as Public Input
+    composer.constrain_to_constant(sum, BlsScalar::zero(), Some(-BlsScalar::from(expected_sum)));
+
+    Ok(())
+}
+
+/// Same as `vector_product_gadget`, but the masked elements are accumulated in
+/// a balanced tree (see `reduce_tree`) instead of a linear chain.
+/// Returns `LengthMismatch` if both vectors don't have the same length.
+pub fn vector_product_tree_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    bits_vector: &Vec<AllocatedScalar>,
+    value: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    if vector.len() != bits_vector.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let mut masked = Vec::with_capacity(vector.len());
+    for (elem, bit) in vector.iter().zip(bits_vector.iter()) {
+        let left = composer.mul(BlsScalar::one(), elem.var, bit.var, BlsScalar::zero(), None);
+        let right = composer.mul(
+            BlsScalar::one(),
+            value.var,
+            bit.var,
+            BlsScalar::zero(),
+            None,
+        );
+        composer.assert_equal(left, right);
+        masked.push(left);
+    }
+    let accumulator = reduce_tree(composer, masked, ReduceOp::Add);
+    composer.assert_equal(accumulator, value.var);
+
+    Ok(())
+}
+
+/// Constrain the product of all of the elements in the vector to be `expected_product`:
+/// v_1 * v_2 * ... * v_n = expected_product
+/// The elements are multiplied in a balanced tree (see `reduce_tree`).
+/// The expected product is not part of the circuit, hence it's placed as Public Input.
+pub fn vector_elements_product_tree_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    expected_product: BlsScalar,
+) -> Result<(), GadgetsError> {
+    let vars = vector.iter().map(|elem| elem.var).collect();
+    let product = reduce_tree(composer, vars, ReduceOp::Mul);
+    composer.constrain_to_constant(product, BlsScalar::zero(), Some(-expected_product));
+
+    Ok(())
+}
+
 /// Constrain the product of vector[i] * bits_vector[i] == value * bits_vector[i]
 pub fn vector_product_gadget(
     composer: &mut StandardComposer,
@@ -132,9 +262,25 @@ pub fn vector_product_gadget(
     bits_vector: &Vec<AllocatedScalar>,
     value: AllocatedScalar,
 ) -> Result<(), GadgetsError> {
-    // TODO need unit tests for this gadget!
-    assert_eq!(vector.len(), bits_vector.len());
+    vector_product_with_accumulator_gadget(composer, vector, bits_vector, value)?;
+    Ok(())
+}
+
+/// Same as `vector_product_gadget`, but returns the accumulated masked sum
+/// sum(vector[i] * bits_vector[i]), which is constrained to be equal to `value`,
+/// so that it can be reused by the caller.
+/// Returns `LengthMismatch` if both vectors don't have the same length.
+pub fn vector_product_with_accumulator_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    bits_vector: &Vec<AllocatedScalar>,
+    value: AllocatedScalar,
+) -> Result<AllocatedScalar, GadgetsError> {
+    if vector.len() != bits_vector.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
     let mut accumulator: Variable = composer.zero_var();
+    let mut accumulator_scalar = BlsScalar::zero();
     for i in 0..vector.len() {
         let left = composer.mul(
             BlsScalar::one(),
@@ -157,12 +303,15 @@ pub fn vector_product_gadget(
             BlsScalar::zero(),
             None,
         );
+        accumulator_scalar += vector[i].scalar * bits_vector[i].scalar;
     }
 
-    // // and constrain the accumulator to be equal to it
     composer.assert_equal(accumulator, value.var);
 
-    Ok(())
+    Ok(AllocatedScalar {
+        var: accumulator,
+        scalar: accumulator_scalar,
+    })
 }
 
 /// Analogous to vector_non_membership_gadget
@@ -171,6 +320,18 @@ pub fn set_membership_gadget(
     vector: &Vec<BlsScalar>,
     assigned_value: AllocatedScalar,
 ) -> Result<(), GadgetsError> {
+    set_membership_with_index_gadget(composer, vector, assigned_value)?;
+    Ok(())
+}
+
+/// Same as `set_membership_gadget`, but returns the one-hot bit vector which
+/// identifies the index of `assigned_value` inside of the vector, so it can be
+/// used to select values from a parallel vector.
+pub fn set_membership_with_index_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<BlsScalar>,
+    assigned_value: AllocatedScalar,
+) -> Result<Vec<AllocatedScalar>, GadgetsError> {
     let mut assigned_set: Vec<AllocatedScalar> = Vec::with_capacity(vector.len());
     for elem in vector.iter() {
         // Since the vector forms part of the circuit,
@@ -189,12 +350,12 @@ pub fn set_membership_gadget(
     for bit in bit_map {
         let bit_assigned = AllocatedScalar::allocate(composer, BlsScalar::from(bit));
         assigned_bits.push(bit_assigned);
-        assert!(bit_gadget(composer, bit_assigned).is_ok());
+        bit_gadget(composer, bit_assigned)?;
     }
-    assert!(vector_sum_gadget(composer, &assigned_bits, 1).is_ok());
+    vector_sum_gadget(composer, &assigned_bits, 1)?;
 
-    assert!(vector_product_gadget(composer, &assigned_set, &assigned_bits, assigned_value).is_ok());
-    Ok(())
+    vector_product_gadget(composer, &assigned_set, &assigned_bits, assigned_value)?;
+    Ok(assigned_bits)
 }
 
 /// Given a `set_length`, construct a circuit
@@ -256,3 +417,1500 @@ pub fn set_uniqueness_gadget(
     }
     Ok(())
 }
+
+/// Constrain two vectors to be equal element-wise: a_i == b_i for every index.
+/// Both vectors are private, and this gadget is mostly useful to bind the outputs
+/// of two different computations together.
+/// Returns `LengthMismatch` if the vectors don't have the same length.
+pub fn vectors_equal_gadget(
+    composer: &mut StandardComposer,
+    a: &Vec<AllocatedScalar>,
+    b: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    if a.len() != b.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    for (a_i, b_i) in a.iter().zip(b.iter()) {
+        composer.assert_equal(a_i.var, b_i.var);
+    }
+
+    Ok(())
+}
+
+/// Returns the prefix sums of the vector, i.e. a vector `out` s.t.:
+/// out_i = v_1 + v_2 + ... + v_i
+/// Each partial accumulation is constrained as out_i = out_(i-1) + v_i,
+/// exposing the intermediate values that `vector_sum_gadget` hides.
+pub fn prefix_sum_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+) -> Vec<AllocatedScalar> {
+    let mut accumulator = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+
+    let mut prefix_sums: Vec<AllocatedScalar> = Vec::with_capacity(vector.len());
+    for elem in vector.iter() {
+        accumulator = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), accumulator.var),
+                (BlsScalar::one(), elem.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: accumulator.scalar + elem.scalar,
+        };
+        prefix_sums.push(accumulator);
+    }
+
+    prefix_sums
+}
+
+/// Constrain `value` to be a (probabilistic) member of the public Bloom filter
+/// `filter`, given the positions `hash_indices` of its bits.
+/// The `i`-th position is computed in the circuit by `hash_fn`, which adds the
+/// constraints of the `i`-th hash function and returns its output already reduced
+/// into [0, filter.len()), and is constrained to match the `i`-th one of `hash_indices`.
+/// Both the filter and the positions form part of the circuit, so each indexed
+/// bit is allocated as a constant and constrained to be set.
+/// Returns `IndexOutOfBounds` if any of the positions doesn't fit in the filter.
+pub fn bloom_membership_gadget<F>(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    hash_indices: &[usize],
+    filter: &[bool],
+    hash_fn: F,
+) -> Result<(), GadgetsError>
+where
+    F: Fn(&mut StandardComposer, AllocatedScalar, usize) -> Result<AllocatedScalar, GadgetsError>,
+{
+    for (i, index) in hash_indices.iter().enumerate() {
+        let bit = filter.get(*index).ok_or(GadgetsError::IndexOutOfBounds)?;
+
+        // The position must be the output of the `i`-th hash function on `value`
+        let position = hash_fn(composer, value, i)?;
+        composer.constrain_to_constant(position.var, BlsScalar::from(*index as u64), None);
+
+        // Since the filter forms part of the circuit, the bit is allocated
+        // as a constant and then constrained to be set.
+        let bit_assigned =
+            composer.add_witness_to_circuit_description(BlsScalar::from(*bit as u64));
+        composer.constrain_to_constant(bit_assigned, BlsScalar::one(), None);
+    }
+
+    Ok(())
+}
+
+/// Constrain the vector to contain exactly `n` non-zero elements, all of them distinct.
+/// Zero elements are treated as padding, so they can be repeated and don't count
+/// towards the cardinality of the set. This allows to prove the size of a set
+/// using fixed-length vectors.
+/// The cardinality `n` is not part of the circuit, hence it's placed as Public Input.
+pub fn set_cardinality_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    n: u64,
+) -> Result<(), GadgetsError> {
+    // Compute a flag for each element which is 1 if it's non-zero and 0 otherwise
+    let non_zero_flags: Vec<AllocatedScalar> = vector
+        .iter()
+        .map(|elem| non_zero_flag(composer, *elem))
+        .collect();
+
+    // For each pair of non-zero elements, their difference must be non-zero
+    for i in 0..vector.len() {
+        for j in (i + 1)..vector.len() {
+            let both_non_zero = AllocatedScalar {
+                var: composer.mul(
+                    BlsScalar::one(),
+                    non_zero_flags[i].var,
+                    non_zero_flags[j].var,
+                    BlsScalar::zero(),
+                    None,
+                ),
+                scalar: non_zero_flags[i].scalar * non_zero_flags[j].scalar,
+            };
+            let diff = composer.add(
+                (BlsScalar::one(), vector[i].var),
+                (-BlsScalar::one(), vector[j].var),
+                BlsScalar::zero(),
+                None,
+            );
+            let diff_scalar = vector[i].scalar - vector[j].scalar;
+
+            let diff_inv: BlsScalar;
+            if both_non_zero.scalar == BlsScalar::zero() {
+                // Any value would do, since the difference is not constrained
+                diff_inv = BlsScalar::zero();
+            } else {
+                let inverse = diff_scalar.invert();
+                if inverse.is_some().unwrap_u8() == 1u8 {
+                    // Safe to unwrap here.
+                    diff_inv = inverse.unwrap();
+                } else {
+                    return Err(GadgetsError::NonExistingInverse);
+                }
+            }
+            let diff_inv_assigned = AllocatedScalar::allocate(composer, diff_inv);
+
+            // diff * diff_inv = both_non_zero
+            composer.poly_gate(
+                diff,
+                diff_inv_assigned.var,
+                both_non_zero.var,
+                BlsScalar::one(),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+                -BlsScalar::one(),
+                BlsScalar::zero(),
+                None,
+            );
+        }
+    }
+
+    // Finally, the amount of non-zero elements should be `n`
+    vector_sum_gadget(composer, &non_zero_flags, n)
+}
+
+/// Constrain the amounts of a transaction to balance, i.e.:
+/// sum(inputs) = sum(outputs) + fee
+/// NOTE that the amounts are assumed to be range-checked by the caller
+/// (see `RangeGadgets::all_in_range_gadget`), otherwise the sums could overflow.
+pub fn balance_gadget(
+    composer: &mut StandardComposer,
+    inputs: &Vec<AllocatedScalar>,
+    outputs: &Vec<AllocatedScalar>,
+    fee: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    // Accumulate sum(inputs) - sum(outputs) - fee, which should be zero
+    let mut accumulator: Variable = composer.zero_var();
+    for input in inputs.iter() {
+        accumulator = composer.add(
+            (BlsScalar::one(), accumulator),
+            (BlsScalar::one(), input.var),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    for output in outputs.iter().chain(core::iter::once(&fee)) {
+        accumulator = composer.add(
+            (BlsScalar::one(), accumulator),
+            (-BlsScalar::one(), output.var),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.constrain_to_constant(accumulator, BlsScalar::zero(), None);
+
+    Ok(())
+}
+
+/// Constrain the private vector `a` to match the public vector `b` at every index
+/// where the corresponding `mask` bit is set, i.e. for each i:
+/// mask_i * (a_i - b_i) = 0
+/// Positions where `mask_i` is zero are left unconstrained, which allows to reveal
+/// only selected entries of a committed vector.
+/// Returns `LengthMismatch` if the three vectors don't have the same length.
+pub fn masked_equal_gadget(
+    composer: &mut StandardComposer,
+    a: &Vec<AllocatedScalar>,
+    b: &[BlsScalar],
+    mask: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    if a.len() != b.len() || a.len() != mask.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    for ((a_i, b_i), mask_i) in a.iter().zip(b.iter()).zip(mask.iter()) {
+        bit_gadget(composer, *mask_i)?;
+        // Since `b` forms part of the circuit, it's placed as a selector:
+        // mask_i * a_i - b_i * mask_i = 0
+        let zero = composer.zero_var();
+        composer.poly_gate(
+            mask_i.var,
+            a_i.var,
+            zero,
+            BlsScalar::one(),
+            -b_i,
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+
+    Ok(())
+}
+
+/// Constrain `result` to be the set difference between the public `universe`
+/// and the private `removed` elements, i.e. result = universe \ removed.
+/// Every element of `result` and `removed` is constrained to be a member of `universe`,
+/// and all of them are constrained to be distinct from one another. Since their amount
+/// matches the size of `universe`, this ensures that together they cover it exactly.
+/// NOTE that the elements of `universe` are assumed to be distinct. As with
+/// `set_uniqueness_gadget`, the verifier needs to provide distinct placeholders.
+/// Returns `LengthMismatch` if the lengths of `result` and `removed` don't add up
+/// to the length of `universe`.
+pub fn set_difference_gadget(
+    composer: &mut StandardComposer,
+    universe: &[BlsScalar],
+    removed: &Vec<AllocatedScalar>,
+    result: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    if result.len() + removed.len() != universe.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let universe = universe.to_vec();
+    let mut elements: Vec<AllocatedScalar> = Vec::with_capacity(universe.len());
+    for elem in result.iter().chain(removed.iter()) {
+        set_membership_gadget(composer, &universe, *elem)?;
+        elements.push(*elem);
+    }
+
+    // Uniqueness is only meaningful for two or more elements
+    if elements.len() >= 2 {
+        set_uniqueness_gadget(composer, &elements)?;
+    }
+
+    Ok(())
+}
+
+/// Constrain `value` to be a member of at least one of the public `sets`.
+/// For each set, a membership flag is computed, which is 1 if the product
+/// (value - s_1) * (value - s_2) * ... * (value - s_n) is zero, and 0 otherwise.
+/// Then, the product of (1 - flag) over all of the sets is constrained to be zero.
+pub fn membership_in_any_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    sets: &[Vec<BlsScalar>],
+) -> Result<(), GadgetsError> {
+    let one = composer.add_witness_to_circuit_description(BlsScalar::one());
+    let mut none_member = one;
+    for set in sets.iter() {
+        let flag = membership_flag(composer, value, set);
+        // 1 - flag
+        let not_member = composer.add(
+            (-BlsScalar::one(), flag),
+            (BlsScalar::zero(), flag),
+            BlsScalar::one(),
+            None,
+        );
+        none_member = composer.mul(
+            BlsScalar::one(),
+            none_member,
+            not_member,
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.constrain_to_constant(none_member, BlsScalar::zero(), None);
+
+    Ok(())
+}
+
+// Returns 1 if `value` is a member of the public `set` and 0 otherwise.
+// The set forms part of the circuit, and the flag is computed by checking
+// whether the product of the differences between `value` and each element is zero.
+fn membership_flag(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    set: &[BlsScalar],
+) -> Variable {
+    let one = composer.add_witness_to_circuit_description(BlsScalar::one());
+    let mut product = AllocatedScalar {
+        var: one,
+        scalar: BlsScalar::one(),
+    };
+    for elem in set.iter() {
+        // value - elem
+        let diff = composer.add(
+            (BlsScalar::one(), value.var),
+            (BlsScalar::zero(), value.var),
+            -elem,
+            None,
+        );
+        product = AllocatedScalar {
+            var: composer.mul(BlsScalar::one(), product.var, diff, BlsScalar::zero(), None),
+            scalar: product.scalar * (value.scalar - elem),
+        };
+    }
+
+    let zero = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    maybe_equal(composer, product, zero)
+}
+
+/// Constrain `value` to not be a member of any of the public `sets`.
+/// This is `vector_non_membership_gadget` applied to each one of the sets, hence
+/// `NonExistingInverse` is returned if `value` is part of any of them.
+pub fn non_membership_in_all_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    sets: &[Vec<BlsScalar>],
+) -> Result<(), GadgetsError> {
+    for set in sets.iter() {
+        vector_non_membership_gadget(composer, set, value)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the one-hot encoding of `index` as a vector of `len` bits, i.e.
+/// bit_i = 1 if i = index and 0 otherwise.
+/// The bits are constrained to add up to one and to satisfy
+/// 0 * bit_0 + 1 * bit_1 + ... + (len - 1) * bit_(len - 1) = index,
+/// while `index` is constrained to lie in [0, len) by proving that
+/// len - 1 - index fits in `n_bits` bits.
+pub fn index_to_onehot_gadget(
+    composer: &mut StandardComposer,
+    index: AllocatedScalar,
+    len: usize,
+    n_bits: usize,
+) -> Result<Vec<AllocatedScalar>, GadgetsError> {
+    // len - 1 - index
+    let max_index = BlsScalar::from(len as u64) - BlsScalar::one();
+    let index_slack = AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), index.var),
+            (BlsScalar::zero(), index.var),
+            max_index,
+            None,
+        ),
+        scalar: max_index - index.scalar,
+    };
+    range_gadget(composer, index_slack, n_bits)?;
+
+    let mut bits: Vec<AllocatedScalar> = Vec::with_capacity(len);
+    let mut bits_sum: Variable = composer.zero_var();
+    let mut weighted_sum: Variable = composer.zero_var();
+    for i in 0..len {
+        let position = BlsScalar::from(i as u64);
+        let bit =
+            AllocatedScalar::allocate(composer, BlsScalar::from((position == index.scalar) as u64));
+        bit_gadget(composer, bit)?;
+
+        bits_sum = composer.add(
+            (BlsScalar::one(), bits_sum),
+            (BlsScalar::one(), bit.var),
+            BlsScalar::zero(),
+            None,
+        );
+        weighted_sum = composer.add(
+            (BlsScalar::one(), weighted_sum),
+            (position, bit.var),
+            BlsScalar::zero(),
+            None,
+        );
+        bits.push(bit);
+    }
+    composer.constrain_to_constant(bits_sum, BlsScalar::one(), None);
+    composer.assert_equal(weighted_sum, index.var);
+
+    Ok(bits)
+}
+
+/// Constrain the private vectors `a` and `b` to be equal as multisets, i.e.
+/// `b` is a permutation of `a`.
+/// The prover supplies the permutation matrix `perm` as a witness, which is
+/// validated and then constrained to satisfy b_i = sum_j(perm_ij * a_j).
+/// Returns `LengthMismatch` if the vectors don't have the same length.
+pub fn multiset_equal_gadget(
+    composer: &mut StandardComposer,
+    a: &Vec<AllocatedScalar>,
+    b: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    if a.len() != b.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let a_scalars: Vec<BlsScalar> = a.iter().map(|elem| elem.scalar).collect();
+    let b_scalars: Vec<BlsScalar> = b.iter().map(|elem| elem.scalar).collect();
+    let perm = permutation_matrix_witness(composer, &a_scalars, &b_scalars);
+    permutation_matrix_constraints(composer, &perm);
+
+    for (row, b_i) in perm.iter().zip(b.iter()) {
+        let mut accumulator: Variable = composer.zero_var();
+        for (entry, a_j) in row.iter().zip(a.iter()) {
+            let selected = composer.mul(
+                BlsScalar::one(),
+                entry.var,
+                a_j.var,
+                BlsScalar::zero(),
+                None,
+            );
+            accumulator = composer.add(
+                (BlsScalar::one(), selected),
+                (BlsScalar::one(), accumulator),
+                BlsScalar::zero(),
+                None,
+            );
+        }
+        composer.assert_equal(accumulator, b_i.var);
+    }
+
+    Ok(())
+}
+
+/// Constrain `sorted` to be the sorted (in non-decreasing order) version of `input`.
+/// The prover supplies both vectors, and the gadget proves that `sorted` is sorted
+/// (see `RangeGadgets::is_sorted_gadget`) and a permutation of `input`
+/// (see `multiset_equal_gadget`).
+/// NOTE that the elements are assumed to be range-checked to `n_bits` bits by the caller.
+/// Returns `LengthMismatch` if the vectors don't have the same length.
+pub fn sorted_commitment_gadget(
+    composer: &mut StandardComposer,
+    input: &Vec<AllocatedScalar>,
+    sorted: &Vec<AllocatedScalar>,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    multiset_equal_gadget(composer, input, sorted)?;
+    is_sorted_gadget(composer, sorted, n_bits)
+}
+
+/// Constrain every element of the vector to be equal to the constant `c`,
+/// which forms part of the circuit. An empty vector trivially satisfies it.
+pub fn all_equal_constant_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    c: BlsScalar,
+) -> Result<(), GadgetsError> {
+    for elem in vector.iter() {
+        composer.constrain_to_constant(elem.var, c, None);
+    }
+
+    Ok(())
+}
+
+/// Constrain `b` to be `a` rotated by the private `shift`, i.e.
+/// b_i = a_((i - shift) mod n) for every index.
+/// The shift is encoded as a one-hot vector (see `index_to_onehot_gadget`), which
+/// constrains it to lie in [0, n), and each element of `b` is constrained to be
+/// the element of `a` selected by it.
+/// Returns `LengthMismatch` if the vectors don't have the same length.
+pub fn is_rotation_gadget(
+    composer: &mut StandardComposer,
+    a: &Vec<AllocatedScalar>,
+    b: &Vec<AllocatedScalar>,
+    shift: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    let n = a.len();
+    if b.len() != n {
+        return Err(GadgetsError::LengthMismatch);
+    }
+    if n == 0 {
+        return Ok(());
+    }
+
+    // n - 1 - shift is lower than n, so it fits in the bits needed to represent n
+    let n_bits = (usize::BITS - n.leading_zeros()) as usize;
+    let shift_bits = index_to_onehot_gadget(composer, shift, n, n_bits)?;
+
+    for (i, b_i) in b.iter().enumerate() {
+        // sum_k(shift_k * a_((i - k) mod n))
+        let mut accumulator: Variable = composer.zero_var();
+        for (k, shift_k) in shift_bits.iter().enumerate() {
+            let selected = composer.mul(
+                BlsScalar::one(),
+                shift_k.var,
+                a[(i + n - k) % n].var,
+                BlsScalar::zero(),
+                None,
+            );
+            accumulator = composer.add(
+                (BlsScalar::one(), selected),
+                (BlsScalar::one(), accumulator),
+                BlsScalar::zero(),
+                None,
+            );
+        }
+        composer.assert_equal(accumulator, b_i.var);
+    }
+
+    Ok(())
+}
+
+/// Constrain the private vector to be equal as a multiset to the public one,
+/// which forms part of the circuit.
+/// The prover supplies the permutation matrix mapping the public vector into the
+/// private one, which is applied through `MatrixGadgets::apply_permutation_gadget`.
+/// Returns `LengthMismatch` if the vectors don't have the same length.
+pub fn set_equal_public_gadget(
+    composer: &mut StandardComposer,
+    private: &Vec<AllocatedScalar>,
+    public: &[BlsScalar],
+) -> Result<(), GadgetsError> {
+    if private.len() != public.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let private_scalars: Vec<BlsScalar> = private.iter().map(|elem| elem.scalar).collect();
+    let perm = permutation_matrix_witness(composer, public, &private_scalars);
+    apply_permutation_gadget(composer, &perm, public, private)
+}
+
+/// Returns the checksum of the vector, i.e. the sum of its elements modulo `modulus`
+/// (see `RangeGadgets::mod_reduce_gadget`).
+/// The sum is assumed to fit in `n_bits` bits.
+pub fn checksum_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    modulus: u64,
+    n_bits: usize,
+) -> Result<AllocatedScalar, GadgetsError> {
+    let mut sum = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for elem in vector.iter() {
+        sum = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), sum.var),
+                (BlsScalar::one(), elem.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: sum.scalar + elem.scalar,
+        };
+    }
+
+    mod_reduce_gadget(composer, sum, modulus, n_bits)
+}
+
+/// Constrain `value` to be equal to one of the public `options`, which form
+/// part of the circuit, through the product-zero form:
+/// (value - o_1) * (value - o_2) * ... * (value - o_n) = 0
+/// Unlike `set_membership_gadget`, no bit-map is allocated, so it's cheaper
+/// for small sets of options.
+pub fn one_of_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    options: &[BlsScalar],
+) -> Result<(), GadgetsError> {
+    let diffs: Vec<Variable> = options
+        .iter()
+        .map(|option| {
+            composer.add(
+                (BlsScalar::one(), value.var),
+                (BlsScalar::zero(), value.var),
+                -option,
+                None,
+            )
+        })
+        .collect();
+    let product = reduce_tree(composer, diffs, ReduceOp::Mul);
+    composer.constrain_to_constant(product, BlsScalar::zero(), None);
+
+    Ok(())
+}
+
+/// Constrain `tag` to be a valid discriminant of an enum, i.e. one of the
+/// public `variants` (see `one_of_gadget`).
+pub fn enum_discriminant_gadget(
+    composer: &mut StandardComposer,
+    tag: AllocatedScalar,
+    variants: &[u64],
+) -> Result<(), GadgetsError> {
+    let variants: Vec<BlsScalar> = variants.iter().map(|v| BlsScalar::from(*v)).collect();
+    one_of_gadget(composer, tag, &variants)
+}
+
+/// Returns the inner product of the vectors, i.e. a_1 * b_1 + a_2 * b_2 + ... + a_n * b_n
+/// Returns `LengthMismatch` if the vectors don't have the same length.
+pub fn inner_product_gadget(
+    composer: &mut StandardComposer,
+    a: &Vec<AllocatedScalar>,
+    b: &Vec<AllocatedScalar>,
+) -> Result<AllocatedScalar, GadgetsError> {
+    if a.len() != b.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let mut accumulator = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    for (a_i, b_i) in a.iter().zip(b.iter()) {
+        // a_i * b_i + accumulator
+        let var = composer.mul(BlsScalar::one(), a_i.var, b_i.var, BlsScalar::zero(), None);
+        accumulator = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), var),
+                (BlsScalar::one(), accumulator.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: accumulator.scalar + a_i.scalar * b_i.scalar,
+        };
+    }
+
+    Ok(accumulator)
+}
+
+/// Constrain the weighted average of `values` to lie in [lo, hi), i.e.
+/// lo <= sum(v_i * w_i) / sum(w_i) < hi
+/// To avoid the division, the bounds are cross-multiplied instead:
+/// lo * sum(w_i) <= sum(v_i * w_i) < hi * sum(w_i)
+/// Both sides of the inequalities are assumed to be representable in `n_bits` bits.
+/// Returns `LengthMismatch` if the vectors don't have the same length.
+pub fn weighted_average_in_range_gadget(
+    composer: &mut StandardComposer,
+    values: &Vec<AllocatedScalar>,
+    weights: &Vec<AllocatedScalar>,
+    lo: u64,
+    hi: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let weighted_sum = inner_product_gadget(composer, values, weights)?;
+    let weights_vars = weights.iter().map(|w| w.var).collect();
+    let weights_sum = AllocatedScalar {
+        var: reduce_tree(composer, weights_vars, ReduceOp::Add),
+        scalar: weights
+            .iter()
+            .fold(BlsScalar::zero(), |sum, w| sum + w.scalar),
+    };
+
+    // sum(v_i * w_i) - lo * sum(w_i) >= 0
+    let lo = BlsScalar::from(lo);
+    let lower_slack = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), weighted_sum.var),
+            (-lo, weights_sum.var),
+            BlsScalar::zero(),
+            None,
+        ),
+        scalar: weighted_sum.scalar - lo * weights_sum.scalar,
+    };
+    range_gadget(composer, lower_slack, n_bits)?;
+
+    // hi * sum(w_i) - sum(v_i * w_i) - 1 >= 0
+    let hi = BlsScalar::from(hi);
+    let upper_slack = AllocatedScalar {
+        var: composer.add(
+            (hi, weights_sum.var),
+            (-BlsScalar::one(), weighted_sum.var),
+            -BlsScalar::one(),
+            None,
+        ),
+        scalar: hi * weights_sum.scalar - weighted_sum.scalar - BlsScalar::one(),
+    };
+    range_gadget(composer, upper_slack, n_bits)
+}
+
+/// Constrain `value` to be a member of the public `set` (see `set_membership_gadget`)
+/// and to satisfy the `predicate`, which is a closure adding the constraints
+/// which define the property (e.g. a range check) for the matched value.
+pub fn member_satisfying_gadget<F>(
+    composer: &mut StandardComposer,
+    set: &[BlsScalar],
+    value: AllocatedScalar,
+    predicate: F,
+) -> Result<(), GadgetsError>
+where
+    F: FnOnce(&mut StandardComposer, AllocatedScalar) -> Result<(), GadgetsError>,
+{
+    set_membership_gadget(composer, &set.to_vec(), value)?;
+    predicate(composer, value)
+}
+
+/// Constrain the vector to contain at most `k` non-zero elements, without
+/// revealing which ones they are. The amount of non-zero elements is proven to be
+/// lower than or equal to `k` by showing that k - count fits in `n_bits` bits.
+pub fn sparsity_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    k: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let non_zero_flags: Vec<Variable> = vector
+        .iter()
+        .map(|elem| non_zero_flag(composer, *elem).var)
+        .collect();
+    let count_scalar = vector
+        .iter()
+        .filter(|elem| elem.scalar != BlsScalar::zero())
+        .count() as u64;
+    let count = reduce_tree(composer, non_zero_flags, ReduceOp::Add);
+
+    // k - count >= 0
+    let k = BlsScalar::from(k);
+    let slack = AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), count),
+            (BlsScalar::zero(), count),
+            k,
+            None,
+        ),
+        scalar: k - BlsScalar::from(count_scalar),
+    };
+    range_gadget(composer, slack, n_bits)
+}
+
+/// Constrain `after` to be the multiset obtained from `before` by taking out the
+/// elements of `removed` and putting in those of `added`. This is proven by showing
+/// that `before` extended with `added` is a permutation of `after` extended with
+/// `removed` (see `multiset_equal_gadget`).
+/// Returns `LengthMismatch` if the sizes of the vectors aren't consistent with the
+/// transition, i.e. if |before| + |added| != |after| + |removed|.
+pub fn set_delta_gadget(
+    composer: &mut StandardComposer,
+    before: &Vec<AllocatedScalar>,
+    after: &Vec<AllocatedScalar>,
+    added: &Vec<AllocatedScalar>,
+    removed: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    if before.len() + added.len() != after.len() + removed.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let lhs: Vec<AllocatedScalar> = before.iter().chain(added.iter()).copied().collect();
+    let rhs: Vec<AllocatedScalar> = after.iter().chain(removed.iter()).copied().collect();
+    multiset_equal_gadget(composer, &lhs, &rhs)
+}
+
+/// Constrain the `values` whose selector is set to sum up to `target`, selecting
+/// at most `max_count` of them. The selectors are constrained to be bits, the sum is
+/// computed as their inner product with the values (see `inner_product_gadget`),
+/// and max_count - sum(selectors) is proven to fit in `n_bits` bits.
+/// Returns `LengthMismatch` if there isn't one selector per value.
+pub fn bounded_subset_sum_gadget(
+    composer: &mut StandardComposer,
+    values: &Vec<AllocatedScalar>,
+    selectors: &Vec<AllocatedScalar>,
+    target: AllocatedScalar,
+    max_count: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let subset_sum = inner_product_gadget(composer, values, selectors)?;
+    composer.assert_equal(subset_sum.var, target.var);
+
+    for selector in selectors.iter() {
+        composer.boolean_gate(selector.var);
+    }
+    let count_scalar = selectors
+        .iter()
+        .fold(BlsScalar::zero(), |acc, selector| acc + selector.scalar);
+    let count = reduce_tree(
+        composer,
+        selectors.iter().map(|selector| selector.var).collect(),
+        ReduceOp::Add,
+    );
+
+    // max_count - count >= 0
+    let max_count = BlsScalar::from(max_count);
+    let slack = AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), count),
+            (BlsScalar::zero(), count),
+            max_count,
+            None,
+        ),
+        scalar: max_count - count_scalar,
+    };
+    range_gadget(composer, slack, n_bits)
+}
+
+/// Constrain `value` to be a member of the public `allow` set and not a member of
+/// the public `deny` set, combining `set_membership_gadget` and
+/// `vector_non_membership_gadget`.
+pub fn member_and_not_member_gadget(
+    composer: &mut StandardComposer,
+    value: AllocatedScalar,
+    allow: &[BlsScalar],
+    deny: &[BlsScalar],
+) -> Result<(), GadgetsError> {
+    set_membership_gadget(composer, &allow.to_vec(), value)?;
+    vector_non_membership_gadget(composer, &deny.to_vec(), value)
+}
+
+/// Constrain at least `threshold` elements of the vector to be equal to `value`.
+/// The equality flags (see `maybe_equal`) are summed up, and the count minus
+/// `threshold` is proven to fit in `n_bits` bits.
+pub fn count_matching_at_least_gadget(
+    composer: &mut StandardComposer,
+    vector: &Vec<AllocatedScalar>,
+    value: AllocatedScalar,
+    threshold: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    let matching_flags: Vec<Variable> = vector
+        .iter()
+        .map(|elem| maybe_equal(composer, *elem, value))
+        .collect();
+    let count_scalar = vector
+        .iter()
+        .filter(|elem| elem.scalar == value.scalar)
+        .count() as u64;
+    let count = reduce_tree(composer, matching_flags, ReduceOp::Add);
+
+    // count - threshold >= 0
+    let threshold = BlsScalar::from(threshold);
+    let slack = AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), count),
+            (BlsScalar::zero(), count),
+            -threshold,
+            None,
+        ),
+        scalar: BlsScalar::from(count_scalar) - threshold,
+    };
+    range_gadget(composer, slack, n_bits)
+}
+
+/// Constrain every point of the series beyond the first `window` ones to be within
+/// `tolerance` of the average of the `window` points preceding it. To avoid the
+/// division, the comparison is scaled by the window size:
+/// |window * x_i - (x_(i-window) + ... + x_(i-1))| <= window * tolerance
+/// where the windowed sums are computed as differences of prefix sums
+/// (see `prefix_sum_gadget`). Both sides are assumed to be representable in `n_bits` bits.
+/// Returns `IndexOutOfBounds` if `window` is zero, and `BitLengthOverflow` if
+/// window * tolerance doesn't fit in a `u64`.
+pub fn within_moving_average_gadget(
+    composer: &mut StandardComposer,
+    series: &Vec<AllocatedScalar>,
+    window: usize,
+    tolerance: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if window == 0 {
+        return Err(GadgetsError::IndexOutOfBounds);
+    }
+    let scaled_tolerance = tolerance
+        .checked_mul(window as u64)
+        .ok_or(GadgetsError::BitLengthOverflow)?;
+
+    let prefix_sums = prefix_sum_gadget(composer, series);
+    let window_scalar = BlsScalar::from(window as u64);
+    for i in window..series.len() {
+        // x_(i-window) + ... + x_(i-1)
+        let window_sum = if i == window {
+            prefix_sums[i - 1]
+        } else {
+            AllocatedScalar {
+                var: composer.add(
+                    (BlsScalar::one(), prefix_sums[i - 1].var),
+                    (-BlsScalar::one(), prefix_sums[i - window - 1].var),
+                    BlsScalar::zero(),
+                    None,
+                ),
+                scalar: prefix_sums[i - 1].scalar - prefix_sums[i - window - 1].scalar,
+            }
+        };
+        let scaled_point = AllocatedScalar {
+            var: composer.add(
+                (window_scalar, series[i].var),
+                (BlsScalar::zero(), series[i].var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: window_scalar * series[i].scalar,
+        };
+        approx_equal_gadget(composer, scaled_point, window_sum, scaled_tolerance, n_bits)?;
+    }
+
+    Ok(())
+}
+
+/// Constrain `(x, y)` to be one of the rows of the public lookup table given by
+/// the columns `table_x` and `table_y`, where `table_x` is strictly increasing.
+/// The prover supplies a one-hot selector over the rows, which is constrained to
+/// consist of bits adding up to one, and to select both `x` and `y`:
+/// sum(s_i * table_x[i]) = x and sum(s_i * table_y[i]) = y
+/// Returns `LengthMismatch` if the columns don't have the same length,
+/// `NonMonotonicSequence` if `table_x` isn't strictly increasing and
+/// `BitLengthOverflow` if any of its entries doesn't fit in `n_bits` bits.
+pub fn monotone_table_lookup_gadget(
+    composer: &mut StandardComposer,
+    x: AllocatedScalar,
+    y: AllocatedScalar,
+    table_x: &[BlsScalar],
+    table_y: &[BlsScalar],
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if table_x.len() != table_y.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+    if table_x
+        .windows(2)
+        .any(|pair| scalar_cmp(&pair[0], &pair[1]) != Ordering::Less)
+    {
+        return Err(GadgetsError::NonMonotonicSequence);
+    }
+    let bound = BlsScalar::from(2).pow(&[n_bits as u64, 0, 0, 0]);
+    if table_x
+        .iter()
+        .any(|elem| scalar_cmp(elem, &bound) != Ordering::Less)
+    {
+        return Err(GadgetsError::BitLengthOverflow);
+    }
+
+    table_lookup_constraint(composer, x, y, table_x, table_y);
+
+    Ok(())
+}
+
+/// Constrain `colors` to be a valid k-coloring of the public graph given by
+/// `edges`, i.e. every color lies in [0, k) and the endpoints of every edge
+/// have different colors (see `ScalarGadgets::assert_not_equal`).
+/// Each color is range-checked to `n_bits` bits, as well as k - 1 - color.
+/// Returns `IndexOutOfBounds` if any edge references a missing vertex, and
+/// `NonExistingInverse` if the endpoints of some edge have the same color.
+pub fn valid_coloring_gadget(
+    composer: &mut StandardComposer,
+    colors: &Vec<AllocatedScalar>,
+    edges: &[(usize, usize)],
+    k: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if edges
+        .iter()
+        .any(|(u, v)| *u >= colors.len() || *v >= colors.len())
+    {
+        return Err(GadgetsError::IndexOutOfBounds);
+    }
+
+    // 0 <= color <= k - 1
+    let max_color = BlsScalar::from(k) - BlsScalar::one();
+    for color in colors.iter() {
+        range_gadget(composer, *color, n_bits)?;
+        let slack = AllocatedScalar {
+            var: composer.add(
+                (-BlsScalar::one(), color.var),
+                (BlsScalar::zero(), color.var),
+                max_color,
+                None,
+            ),
+            scalar: max_color - color.scalar,
+        };
+        range_gadget(composer, slack, n_bits)?;
+    }
+
+    for (u, v) in edges.iter() {
+        assert_not_equal(composer, colors[*u], colors[*v])?;
+    }
+
+    Ok(())
+}
+
+/// Constrain the cells referenced by each of the `groups` (e.g. the rows, columns
+/// and boxes of a Sudoku) to be all different. Instead of inverting every pairwise
+/// difference as `set_uniqueness_gadget` does, the differences of each group are
+/// multiplied together and only their product is proven to be non-zero,
+/// which requires a single inversion per group.
+/// Returns `IndexOutOfBounds` if any group references a missing cell, and
+/// `NonExistingInverse` if some group contains a duplicate.
+pub fn all_different_groups_gadget(
+    composer: &mut StandardComposer,
+    cells: &Vec<AllocatedScalar>,
+    groups: &[Vec<usize>],
+) -> Result<(), GadgetsError> {
+    if groups
+        .iter()
+        .any(|group| group.iter().any(|index| *index >= cells.len()))
+    {
+        return Err(GadgetsError::IndexOutOfBounds);
+    }
+
+    for group in groups.iter() {
+        let pairs: Vec<(AllocatedScalar, AllocatedScalar)> = group
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| group[i + 1..].iter().map(move |b| (*a, *b)))
+            .map(|(a, b)| (cells[a], cells[b]))
+            .collect();
+        distinct_pairs_constraint(composer, &pairs)?;
+    }
+
+    Ok(())
+}
+
+/// Constrain the private vectors `part_a` and `part_b` to partition the public
+/// `universe`, i.e. they are disjoint and together equal to it as multisets
+/// (see `set_equal_public_gadget`). Disjointness is proven by showing that the
+/// product of the differences between every element of `part_a` and every
+/// element of `part_b` is non-zero, which requires a single inversion.
+/// Returns `LengthMismatch` if the sizes of the parts don't add up to the size
+/// of the universe, and `NonExistingInverse` if the parts share an element.
+pub fn partition_gadget(
+    composer: &mut StandardComposer,
+    part_a: &Vec<AllocatedScalar>,
+    part_b: &Vec<AllocatedScalar>,
+    universe: &[BlsScalar],
+) -> Result<(), GadgetsError> {
+    let union: Vec<AllocatedScalar> = part_a.iter().chain(part_b.iter()).copied().collect();
+    set_equal_public_gadget(composer, &union, universe)?;
+
+    let pairs: Vec<(AllocatedScalar, AllocatedScalar)> = part_a
+        .iter()
+        .flat_map(|a| part_b.iter().map(move |b| (*a, *b)))
+        .collect();
+    distinct_pairs_constraint(composer, &pairs)
+}
+
+/// Constrain `chosen` to be one of the public `choices` and `weight_out` to be
+/// its associated weight in the parallel public array `weights`, i.e. a key-value
+/// lookup over public data, done through a one-hot selector over both arrays
+/// (see `monotone_table_lookup_gadget`).
+/// Returns `LengthMismatch` if there isn't one weight per choice.
+pub fn weighted_selection_gadget(
+    composer: &mut StandardComposer,
+    choices: &[BlsScalar],
+    weights: &[BlsScalar],
+    chosen: AllocatedScalar,
+    weight_out: AllocatedScalar,
+) -> Result<(), GadgetsError> {
+    if choices.len() != weights.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+    table_lookup_constraint(composer, chosen, weight_out, choices, weights);
+
+    Ok(())
+}
+
+/// Constrain the Levenshtein distance between the sequences `a` and `b` to be at most `k`.
+/// The prover supplies the dynamic programming matrix D, where D[i][j] is the distance
+/// between the first i elements of `a` and the first j elements of `b`. Its first row
+/// and column are fixed to D[i][0] = i and D[0][j] = j, and every other entry is
+/// constrained to follow the recurrence
+/// D[i][j] = min(D[i-1][j] + 1, D[i][j-1] + 1, D[i-1][j-1] + (a_i != b_j))
+/// by proving that it's lower or equal than the three options (range-checking their
+/// differences to `n_bits` bits) and equal to one of them (see `one_of_gadget`).
+/// Finally, k - D[len(a)][len(b)] is range-checked to `n_bits` bits, so both the
+/// lengths of the sequences and `k` are assumed to be representable in `n_bits` bits.
+pub fn edit_distance_at_most_gadget(
+    composer: &mut StandardComposer,
+    a: &Vec<AllocatedScalar>,
+    b: &Vec<AllocatedScalar>,
+    k: u64,
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    // Compute the witness assignments for the matrix
+    let mut distances: Vec<Vec<u64>> = (0..=a.len())
+        .map(|i| {
+            let mut row = vec![0u64; b.len() + 1];
+            row[0] = i as u64;
+            row
+        })
+        .collect();
+    for (j, distance) in distances[0].iter_mut().enumerate() {
+        *distance = j as u64;
+    }
+    for (i, a_i) in a.iter().enumerate() {
+        for (j, b_j) in b.iter().enumerate() {
+            let substitution = distances[i][j] + (a_i.scalar != b_j.scalar) as u64;
+            let deletion = distances[i][j + 1] + 1;
+            let insertion = distances[i + 1][j] + 1;
+            distances[i + 1][j + 1] = substitution.min(deletion).min(insertion);
+        }
+    }
+
+    let mut previous_row: Vec<AllocatedScalar> = (0..=b.len())
+        .map(|j| {
+            let j = BlsScalar::from(j as u64);
+            AllocatedScalar {
+                var: composer.add_witness_to_circuit_description(j),
+                scalar: j,
+            }
+        })
+        .collect();
+    for (i, a_i) in a.iter().enumerate() {
+        let first = BlsScalar::from(i as u64 + 1);
+        let mut row = vec![AllocatedScalar {
+            var: composer.add_witness_to_circuit_description(first),
+            scalar: first,
+        }];
+        for (j, b_j) in b.iter().enumerate() {
+            let distance =
+                AllocatedScalar::allocate(composer, BlsScalar::from(distances[i + 1][j + 1]));
+
+            // D[i-1][j-1] + 1 - (a_i == b_j)
+            let equal = maybe_equal(composer, *a_i, *b_j);
+            let substitution = AllocatedScalar {
+                var: composer.add(
+                    (BlsScalar::one(), previous_row[j].var),
+                    (-BlsScalar::one(), equal),
+                    BlsScalar::one(),
+                    None,
+                ),
+                scalar: previous_row[j].scalar + BlsScalar::from((a_i.scalar != b_j.scalar) as u64),
+            };
+
+            // The distance is lower or equal than every option and equal to one of them
+            let mut slacks: Vec<Variable> = Vec::with_capacity(3);
+            for (option, offset) in [
+                (substitution, BlsScalar::zero()),
+                (previous_row[j + 1], BlsScalar::one()),
+                (row[j], BlsScalar::one()),
+            ]
+            .iter()
+            {
+                let slack = AllocatedScalar {
+                    var: composer.add(
+                        (BlsScalar::one(), option.var),
+                        (-BlsScalar::one(), distance.var),
+                        *offset,
+                        None,
+                    ),
+                    scalar: option.scalar + offset - distance.scalar,
+                };
+                range_gadget(composer, slack, n_bits)?;
+                slacks.push(slack.var);
+            }
+            let product = reduce_tree(composer, slacks, ReduceOp::Mul);
+            composer.constrain_to_constant(product, BlsScalar::zero(), None);
+
+            row.push(distance);
+        }
+        previous_row = row;
+    }
+
+    // k - D[len(a)][len(b)] >= 0
+    let distance = previous_row[b.len()];
+    let k = BlsScalar::from(k);
+    let slack = AllocatedScalar {
+        var: composer.add(
+            (-BlsScalar::one(), distance.var),
+            (BlsScalar::zero(), distance.var),
+            k,
+            None,
+        ),
+        scalar: k - distance.scalar,
+    };
+    range_gadget(composer, slack, n_bits)
+}
+
+/// Constrain `order` to be a topological order of the public graph given by `edges`,
+/// i.e. the rank of the source of every edge is lower than the rank of its target
+/// (see `RangeGadgets::less_than_gadget`). Since every edge increases the rank by
+/// at least one and at most 2^n_bits, no cycle can be ranked without wrapping around
+/// the field modulus, hence a valid order proves the graph to be acyclic.
+/// The ranks are assumed to be representable in `n_bits` bits.
+/// Returns `IndexOutOfBounds` if any edge references a missing node.
+pub fn is_topologically_ordered_gadget(
+    composer: &mut StandardComposer,
+    order: &Vec<AllocatedScalar>,
+    edges: &[(usize, usize)],
+    n_bits: usize,
+) -> Result<(), GadgetsError> {
+    if edges
+        .iter()
+        .any(|(u, v)| *u >= order.len() || *v >= order.len())
+    {
+        return Err(GadgetsError::IndexOutOfBounds);
+    }
+
+    for (u, v) in edges.iter() {
+        less_than_gadget(composer, order[*u], order[*v], n_bits)?;
+    }
+
+    Ok(())
+}
+
+/// Constrain `output` to be `input` with the elements whose keep bit is 0 removed,
+/// preserving the order of the remaining ones. Every one of `keep_bits` is
+/// constrained to be a bit, and they must add up to the length of `output`.
+/// The position in `output` of the i-th input element is the number of elements
+/// kept before it, p_i = keep_1 + ... + keep_(i-1), so for every output position j:
+/// keep_i * (p_i == j) * (input_i - output_j) = 0
+/// Returns `LengthMismatch` if there isn't one keep bit per input element or if
+/// `output` is longer than `input`.
+pub fn filter_consistency_gadget(
+    composer: &mut StandardComposer,
+    input: &Vec<AllocatedScalar>,
+    keep_bits: &Vec<AllocatedScalar>,
+    output: &Vec<AllocatedScalar>,
+) -> Result<(), GadgetsError> {
+    if input.len() != keep_bits.len() || output.len() > input.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let positions: Vec<AllocatedScalar> = (0..output.len())
+        .map(|j| {
+            let j = BlsScalar::from(j as u64);
+            AllocatedScalar {
+                var: composer.add_witness_to_circuit_description(j),
+                scalar: j,
+            }
+        })
+        .collect();
+    let zero = composer.zero_var();
+    let mut kept = AllocatedScalar {
+        var: zero,
+        scalar: BlsScalar::zero(),
+    };
+    for (elem, keep) in input.iter().zip(keep_bits.iter()) {
+        composer.boolean_gate(keep.var);
+
+        for (position, out) in positions.iter().zip(output.iter()) {
+            let at_position = maybe_equal(composer, kept, *position);
+            let selected = composer.mul(
+                BlsScalar::one(),
+                keep.var,
+                at_position,
+                BlsScalar::zero(),
+                None,
+            );
+            let diff = composer.add(
+                (BlsScalar::one(), elem.var),
+                (-BlsScalar::one(), out.var),
+                BlsScalar::zero(),
+                None,
+            );
+            // selected * (input_i - output_j) = 0
+            composer.poly_gate(
+                selected,
+                diff,
+                zero,
+                BlsScalar::one(),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+                BlsScalar::zero(),
+                None,
+            );
+        }
+
+        kept = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), kept.var),
+                (BlsScalar::one(), keep.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: kept.scalar + keep.scalar,
+        };
+    }
+    composer.constrain_to_constant(kept.var, BlsScalar::from(output.len() as u64), None);
+
+    Ok(())
+}
+
+/// Returns the value associated to `key` in the private key-value pairs given by
+/// `keys` and `values`, or `default` if the key is absent, so that the proof
+/// doesn't fail for missing keys. For each pair, the flag of the first match
+/// m_i = (key == k_i) * (1 - f_(i-1)) is computed, where f_i = f_(i-1) + m_i
+/// is the presence flag, and the result is selected as
+/// sum(m_i * v_i) + (1 - f_n) * default
+/// If the key is repeated, the value of its first occurrence is returned.
+/// Returns `LengthMismatch` if there isn't one value per key.
+pub fn lookup_or_default_gadget(
+    composer: &mut StandardComposer,
+    keys: &Vec<AllocatedScalar>,
+    values: &Vec<AllocatedScalar>,
+    key: AllocatedScalar,
+    default: BlsScalar,
+) -> Result<AllocatedScalar, GadgetsError> {
+    if keys.len() != values.len() {
+        return Err(GadgetsError::LengthMismatch);
+    }
+
+    let zero = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    let mut found = zero;
+    let mut selected = zero;
+    for (k, v) in keys.iter().zip(values.iter()) {
+        let equal = maybe_equal(composer, key, *k);
+        let equal_scalar = BlsScalar::from((key.scalar == k.scalar) as u64);
+
+        // equal - equal * f_(i-1) - m_i = 0
+        let first_match =
+            AllocatedScalar::allocate(composer, equal_scalar * (BlsScalar::one() - found.scalar));
+        composer.poly_gate(
+            equal,
+            found.var,
+            first_match.var,
+            -BlsScalar::one(),
+            BlsScalar::one(),
+            BlsScalar::zero(),
+            -BlsScalar::one(),
+            BlsScalar::zero(),
+            None,
+        );
+        found = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), found.var),
+                (BlsScalar::one(), first_match.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: found.scalar + first_match.scalar,
+        };
+
+        let product = composer.mul(
+            BlsScalar::one(),
+            first_match.var,
+            v.var,
+            BlsScalar::zero(),
+            None,
+        );
+        selected = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), selected.var),
+                (BlsScalar::one(), product),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: selected.scalar + first_match.scalar * v.scalar,
+        };
+    }
+
+    // sum(m_i * v_i) + default - f_n * default
+    Ok(AllocatedScalar {
+        var: composer.add(
+            (BlsScalar::one(), selected.var),
+            (-default, found.var),
+            default,
+            None,
+        ),
+        scalar: selected.scalar + (BlsScalar::one() - found.scalar) * default,
+    })
+}
+
+// Returns 1 if `elem` is non-zero and 0 otherwise.
+fn non_zero_flag(composer: &mut StandardComposer, elem: AllocatedScalar) -> AllocatedScalar {
+    let zero = AllocatedScalar {
+        var: composer.zero_var(),
+        scalar: BlsScalar::zero(),
+    };
+    let is_zero = maybe_equal(composer, elem, zero);
+    // 1 - is_zero
+    let non_zero = composer.add(
+        (-BlsScalar::one(), is_zero),
+        (BlsScalar::zero(), is_zero),
+        BlsScalar::one(),
+        None,
+    );
+    let non_zero_scalar = if elem.scalar == BlsScalar::zero() {
+        BlsScalar::zero()
+    } else {
+        BlsScalar::one()
+    };
+    AllocatedScalar {
+        var: non_zero,
+        scalar: non_zero_scalar,
+    }
+}
+
+// Constrains the elements of every pair to be different, by proving that the
+// product of their differences is non-zero. Hence only a single inversion is needed.
+fn distinct_pairs_constraint(
+    composer: &mut StandardComposer,
+    pairs: &[(AllocatedScalar, AllocatedScalar)],
+) -> Result<(), GadgetsError> {
+    let mut product: Option<AllocatedScalar> = None;
+    for (a, b) in pairs.iter() {
+        let diff = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), a.var),
+                (-BlsScalar::one(), b.var),
+                BlsScalar::zero(),
+                None,
+            ),
+            scalar: a.scalar - b.scalar,
+        };
+        product = Some(match product {
+            None => diff,
+            Some(product) => AllocatedScalar {
+                var: composer.mul(
+                    BlsScalar::one(),
+                    product.var,
+                    diff.var,
+                    BlsScalar::zero(),
+                    None,
+                ),
+                scalar: product.scalar * diff.scalar,
+            },
+        });
+    }
+
+    match product {
+        Some(product) => is_non_zero(composer, product.var, product.scalar),
+        None => Ok(()),
+    }
+}
+
+// Constrains `(x, y)` to be one of the rows of the public table given by the
+// columns `table_x` and `table_y`, which are assumed to have the same length,
+// through a one-hot selector over the rows.
+fn table_lookup_constraint(
+    composer: &mut StandardComposer,
+    x: AllocatedScalar,
+    y: AllocatedScalar,
+    table_x: &[BlsScalar],
+    table_y: &[BlsScalar],
+) {
+    // Compute the witness assignment for the selected row. If (x, y) isn't a row,
+    // no selector satisfies the constraints.
+    let row = table_x
+        .iter()
+        .zip(table_y.iter())
+        .position(|(tx, ty)| *tx == x.scalar && *ty == y.scalar);
+
+    let mut selectors_sum: Variable = composer.zero_var();
+    let mut selected_x: Variable = composer.zero_var();
+    let mut selected_y: Variable = composer.zero_var();
+    for (i, (tx, ty)) in table_x.iter().zip(table_y.iter()).enumerate() {
+        let selector =
+            AllocatedScalar::allocate(composer, BlsScalar::from((row == Some(i)) as u64));
+        composer.boolean_gate(selector.var);
+
+        selectors_sum = composer.add(
+            (BlsScalar::one(), selectors_sum),
+            (BlsScalar::one(), selector.var),
+            BlsScalar::zero(),
+            None,
+        );
+        // Since the table forms part of the circuit, its entries are the coefficients
+        selected_x = composer.add(
+            (BlsScalar::one(), selected_x),
+            (*tx, selector.var),
+            BlsScalar::zero(),
+            None,
+        );
+        selected_y = composer.add(
+            (BlsScalar::one(), selected_y),
+            (*ty, selector.var),
+            BlsScalar::zero(),
+            None,
+        );
+    }
+    composer.constrain_to_constant(selectors_sum, BlsScalar::one(), None);
+    composer.assert_equal(selected_x, x.var);
+    composer.assert_equal(selected_y, y.var);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduce_tree_depth() {
+        let mut composer = StandardComposer::new();
+        for (n, expected_depth) in [
+            (0, 0),
+            (1, 0),
+            (2, 1),
+            (3, 2),
+            (4, 2),
+            (5, 3),
+            (16, 4),
+            (17, 5),
+        ]
+        .iter()
+        {
+            let vars: Vec<Variable> = (0..*n)
+                .map(|i| composer.add_input(BlsScalar::from(i as u64)))
+                .collect();
+            for op in [ReduceOp::Add, ReduceOp::Mul].iter() {
+                let (_, depth) = reduce_tree_with_depth(&mut composer, vars.clone(), *op);
+                assert_eq!(depth, *expected_depth);
+            }
+        }
+    }
+}
@@ -64,3 +64,773 @@ fn test_is_bit() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_assert_bit_decomposition() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let circuit =
+        |composer: &mut StandardComposer, value: u64, bits: &[u64]| -> Result<(), GadgetError> {
+            let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+            let bits: Vec<AllocatedScalar> = bits
+                .iter()
+                .map(|bit| AllocatedScalar::allocate(composer, BlsScalar::from(*bit)))
+                .collect();
+
+            assert_bit_decomposition_gadget(composer, value, &bits)
+        };
+
+    struct TestCase {
+        value: u64,
+        bits: Vec<u64>,
+        expected: bool,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            // 13 = 0b1011 (little-endian)
+            value: 13,
+            bits: vec![1, 0, 1, 1, 0, 0, 0, 0],
+            expected: true,
+        },
+        TestCase {
+            value: 0,
+            bits: vec![0, 0, 0, 0, 0, 0, 0, 0],
+            expected: true,
+        },
+        TestCase {
+            value: 255,
+            bits: vec![1, 1, 1, 1, 1, 1, 1, 1],
+            expected: true,
+        },
+        TestCase {
+            // Tampered bit
+            value: 13,
+            bits: vec![1, 1, 1, 1, 0, 0, 0, 0],
+            expected: false,
+        },
+        TestCase {
+            // Weighted sum is right but the first "bit" isn't a bit
+            value: 13,
+            bits: vec![3, 1, 0, 1, 0, 0, 0, 0],
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), case.value, &case.bits).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0, &[0; 8]).is_ok());
+        verifier.preprocess(&ck).expect("Preprocessing error");
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok())
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err())
+        };
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bit_with_complement() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    // The circuit closure constraints the returned complement to be equal to the
+    // expected one, which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   bit: u64,
+                   complement: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let a = AllocatedScalar::allocate(composer, BlsScalar::from(bit));
+
+        let one_minus_a = bit_gadget_with_complement(composer, a)?;
+        composer.constrain_to_constant(
+            one_minus_a.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(complement)),
+        );
+        Ok(one_minus_a.scalar)
+    };
+
+    struct TestCase {
+        val: u64,
+        complement: u64,
+        expected: bool,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            val: 0,
+            complement: 1,
+            expected: true,
+        },
+        TestCase {
+            val: 1,
+            complement: 0,
+            expected: true,
+        },
+        TestCase {
+            val: 1,
+            complement: 1,
+            expected: false,
+        },
+        TestCase {
+            val: 2,
+            complement: 0,
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.iter() {
+        let mut prover = Prover::new(b"testing");
+        let complement = circuit(prover.mut_cs(), case.val, case.complement);
+        assert_eq!(
+            complement.expect("Bit gadget error"),
+            BlsScalar::one() - BlsScalar::from(case.val)
+        );
+        let pi = prover.mut_cs().construct_dense_pi_vec().clone();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+        verifier.preprocess(&ck).expect("Preprocessing error");
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok())
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err())
+        };
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_cnf_satisfied() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // The circuit closure allocates the assignment and builds the formula out of
+    // `(variable index, negated)` literals.
+    let circuit = |composer: &mut StandardComposer,
+                   assignment: &[u64],
+                   formula: &[Vec<(usize, bool)>]|
+     -> Result<(), GadgetError> {
+        let vars: Vec<AllocatedScalar> = assignment
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let clauses: Vec<Vec<(AllocatedScalar, bool)>> = formula
+            .iter()
+            .map(|clause| {
+                clause
+                    .iter()
+                    .map(|(index, negated)| (vars[*index], *negated))
+                    .collect()
+            })
+            .collect();
+        cnf_satisfied_gadget(composer, &clauses)
+    };
+
+    struct TestCase {
+        formula: Vec<Vec<(usize, bool)>>,
+        assignment: Vec<u64>,
+        expected: bool,
+    }
+
+    // (x0 OR NOT x1) AND (x1 OR x2) AND (NOT x0 OR NOT x2)
+    let satisfiable = vec![
+        vec![(0, false), (1, true)],
+        vec![(1, false), (2, false)],
+        vec![(0, true), (2, true)],
+    ];
+    // x0 AND NOT x0
+    let unsatisfiable = vec![vec![(0, false)], vec![(0, true)]];
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            formula: satisfiable.clone(),
+            assignment: vec![1, 1, 0],
+            expected: true,
+        },
+        TestCase {
+            formula: satisfiable.clone(),
+            assignment: vec![0, 0, 1],
+            expected: true,
+        },
+        TestCase {
+            formula: satisfiable.clone(),
+            assignment: vec![0, 1, 0],
+            expected: false,
+        },
+        TestCase {
+            formula: satisfiable.clone(),
+            assignment: vec![1, 0, 1],
+            expected: false,
+        },
+        TestCase {
+            formula: satisfiable,
+            assignment: vec![1, 2, 0],
+            expected: false,
+        },
+        TestCase {
+            formula: unsatisfiable.clone(),
+            assignment: vec![0],
+            expected: false,
+        },
+        TestCase {
+            formula: unsatisfiable,
+            assignment: vec![1],
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.assignment, &case.formula).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![0; case.assignment.len()];
+        assert!(circuit(verifier.mut_cs(), &placeholder, &case.formula).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bitwise_not() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // The circuit closure constraints the bitwise NOT of the value within 16 bits to be
+    // equal to the expected one, which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   value: u64,
+                   not_value: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+        let not_value_assigned = bitwise_not_gadget(composer, value, 16)?;
+        composer.constrain_to_constant(
+            not_value_assigned.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(not_value)),
+        );
+        Ok(not_value_assigned.scalar)
+    };
+
+    for value in [0u64, 1, 0x00ff, 0xa5a5, 0xffff].iter() {
+        let not_value = !value & 0xffff;
+
+        let mut prover = Prover::new(b"testing");
+        let result = circuit(prover.mut_cs(), *value, not_value).expect("Bitwise NOT error");
+        assert_eq!(result, BlsScalar::from(not_value));
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+    }
+
+    // A value which doesn't fit in 16 bits should fail
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), 0x1_0000, 0).is_ok());
+    let pi = prover.mut_cs().construct_dense_pi_vec();
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::new(b"testing");
+    assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier.verify(&proof, &vk, &pi).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_flag_groups() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // The circuit closure checks 6 flags split in the exclusive groups [0, 3) and [3, 5),
+    // leaving the last flag free.
+    let groups = [(0, 3), (3, 5)];
+    let circuit = |composer: &mut StandardComposer,
+                   flags: &[u64],
+                   groups: &[(usize, usize)]|
+     -> Result<(), GadgetError> {
+        let bits: Vec<AllocatedScalar> = flags
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        flag_groups_gadget(composer, &bits, groups)
+    };
+
+    struct TestCase {
+        flags: Vec<u64>,
+        expected: bool,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            flags: vec![0, 0, 0, 0, 0, 0],
+            expected: true,
+        },
+        TestCase {
+            flags: vec![0, 1, 0, 1, 0, 1],
+            expected: true,
+        },
+        TestCase {
+            flags: vec![1, 0, 0, 0, 0, 1],
+            expected: true,
+        },
+        TestCase {
+            flags: vec![1, 0, 1, 0, 0, 0],
+            expected: false,
+        },
+        TestCase {
+            flags: vec![0, 0, 0, 1, 1, 0],
+            expected: false,
+        },
+        TestCase {
+            flags: vec![0, 0, 0, 0, 0, 2],
+            expected: false,
+        },
+    ];
+
+    // Groups out of the bits bounds are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[0; 6], &[(4, 7)]).is_err());
+
+    for case in test_cases.iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.flags, &groups).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 6], &groups).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_conditional_parity() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // The circuit closure constraints the parity of the selected bits to be equal
+    // to the expected one, which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   bits: &[u64],
+                   selectors: &[u64],
+                   parity: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let bits: Vec<AllocatedScalar> = bits
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let selectors: Vec<AllocatedScalar> = selectors
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let parity_assigned = conditional_parity_gadget(composer, &bits, &selectors)?;
+        composer.constrain_to_constant(
+            parity_assigned.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(parity)),
+        );
+        Ok(parity_assigned.scalar)
+    };
+
+    struct TestCase {
+        bits: Vec<u64>,
+        selectors: Vec<u64>,
+        parity: u64,
+        expected: bool,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        // All selectors on
+        TestCase {
+            bits: vec![1, 0, 1, 1, 0],
+            selectors: vec![1, 1, 1, 1, 1],
+            parity: 1,
+            expected: true,
+        },
+        // All selectors off
+        TestCase {
+            bits: vec![1, 0, 1, 1, 0],
+            selectors: vec![0, 0, 0, 0, 0],
+            parity: 0,
+            expected: true,
+        },
+        // Mixed selectors
+        TestCase {
+            bits: vec![1, 0, 1, 1, 0],
+            selectors: vec![1, 1, 0, 1, 1],
+            parity: 0,
+            expected: true,
+        },
+        TestCase {
+            bits: vec![1, 0, 1, 1, 0],
+            selectors: vec![1, 1, 0, 1, 1],
+            parity: 1,
+            expected: false,
+        },
+        // Non-boolean selector
+        TestCase {
+            bits: vec![1, 0, 1, 1, 0],
+            selectors: vec![2, 0, 0, 0, 0],
+            parity: 0,
+            expected: false,
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[1, 0], &[1], 1).is_err());
+
+    for case in test_cases.iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.bits, &case.selectors, case.parity).is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 5], &[0; 5], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bitwise_or_reduce() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    // The circuit closure constraints the bitwise OR of the values within 16 bits to be
+    // equal to the expected one, which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   values: &[u64],
+                   or_value: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let values: Vec<AllocatedScalar> = values
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let or_assigned = bitwise_or_reduce_gadget(composer, &values, 16)?;
+        composer.constrain_to_constant(
+            or_assigned.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(or_value)),
+        );
+        Ok(or_assigned.scalar)
+    };
+
+    let test_cases: Vec<Vec<u64>> = vec![
+        vec![0, 0, 0],
+        vec![0x0001, 0x0100, 0x8000],
+        vec![0x00ff, 0x0ff0, 0xf00f],
+        vec![0xa5a5, 0x5a5a, 0x0000],
+    ];
+
+    for values in test_cases.iter() {
+        let or_value = values.iter().fold(0, |acc, x| acc | x);
+
+        let mut prover = Prover::new(b"testing");
+        let result = circuit(prover.mut_cs(), values, or_value).expect("Bitwise OR error");
+        assert_eq!(result, BlsScalar::from(or_value));
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 3], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+    }
+
+    // A wrong OR value should fail
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[0x00f0, 0x000f, 0], 0x0fff).is_ok());
+    let pi = prover.mut_cs().construct_dense_pi_vec();
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::new(b"testing");
+    assert!(circuit(verifier.mut_cs(), &[0; 3], 0).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier.verify(&proof, &vk, &pi).is_err());
+
+    // A value which doesn't fit in 16 bits should fail
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[0x1_0000, 0, 0], 0).is_ok());
+    let pi = prover.mut_cs().construct_dense_pi_vec();
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::new(b"testing");
+    assert!(circuit(verifier.mut_cs(), &[0; 3], 0).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier.verify(&proof, &vk, &pi).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_cidr_membership() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let ipv4 = |a: u64, b: u64, c: u64, d: u64| (a << 24) | (b << 16) | (c << 8) | d;
+
+    // The circuit closure checks the address to lie in 192.168.1.0/24.
+    let prefix = ipv4(192, 168, 1, 0) as u32;
+    let circuit = |composer: &mut StandardComposer, ip: u64| -> Result<(), GadgetError> {
+        let ip = AllocatedScalar::allocate(composer, BlsScalar::from(ip));
+        cidr_membership_gadget(composer, ip, prefix, 24)
+    };
+
+    let test_cases = vec![
+        (ipv4(192, 168, 1, 0), true),
+        (ipv4(192, 168, 1, 77), true),
+        (ipv4(192, 168, 1, 255), true),
+        (ipv4(192, 168, 2, 1), false),
+        (ipv4(10, 168, 1, 77), false),
+        // Addresses which don't fit in 32 bits should fail
+        ((1 << 32) | ipv4(192, 168, 1, 77), false),
+    ];
+
+    // Prefixes longer than the address are rejected
+    let mut prover = Prover::new(b"testing");
+    let ip = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::zero());
+    assert!(cidr_membership_gadget(prover.mut_cs(), ip, prefix, 33).is_err());
+
+    for (ip, expected) in test_cases.into_iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), ip).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_gray_code_successor() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    // The circuit closure checks the transition between two 4-bit codes.
+    let circuit =
+        |composer: &mut StandardComposer, prev: u64, next: u64| -> Result<(), GadgetError> {
+            let prev = AllocatedScalar::allocate(composer, BlsScalar::from(prev));
+            let next = AllocatedScalar::allocate(composer, BlsScalar::from(next));
+            gray_code_successor_gadget(composer, prev, next, 4)
+        };
+
+    // Every transition of the reflected binary Gray code is valid
+    let gray = |i: u64| i ^ (i >> 1);
+    let mut test_cases: Vec<(u64, u64, bool)> =
+        (0..15).map(|i| (gray(i), gray(i + 1), true)).collect();
+    test_cases.extend(vec![
+        // Same code
+        (0b0110, 0b0110, false),
+        // Two bits changed
+        (0b0110, 0b0101, false),
+        // All bits changed
+        (0b0000, 0b1111, false),
+        // Codes which don't fit in 4 bits should fail
+        (0b0110, 0b10110, false),
+    ]);
+
+    for (prev, next, expected) in test_cases.into_iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), prev, next).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_grid_parity() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 6, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 5)?;
+
+    // The circuit closure checks a 4 x 3 grid to be a checkerboard.
+    let circuit = |composer: &mut StandardComposer, cells: &[u64]| -> Result<(), GadgetError> {
+        let cells: Vec<AllocatedScalar> = cells
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        grid_parity_gadget(composer, &cells, 4, 3)
+    };
+
+    let checkerboard = vec![0, 1, 0, 1, 1, 0, 1, 0, 0, 1, 0, 1];
+    let mut mispainted = checkerboard.clone();
+    mispainted[6] = 0;
+    let inverted: Vec<u64> = checkerboard.iter().map(|x| 1 - x).collect();
+
+    let test_cases = vec![(checkerboard, true), (mispainted, false), (inverted, false)];
+
+    // The grid must have width * height cells
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[0, 1, 0, 1]).is_err());
+
+    for (cells, expected) in test_cases.into_iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &cells).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 12]).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_twos_complement_negate() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    // The circuit closure checks the negation of 8-bit two's complement values.
+    let circuit =
+        |composer: &mut StandardComposer, value: u64, negated: u64| -> Result<(), GadgetError> {
+            let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+            let negated = AllocatedScalar::allocate(composer, BlsScalar::from(negated));
+            twos_complement_negate_gadget(composer, value, negated, 8)
+        };
+
+    // Encoding of a signed value as an 8-bit two's complement one
+    let encode = |x: i8| x as u8 as u64;
+
+    let test_cases = vec![
+        (encode(5), encode(-5), true),
+        (encode(-5), encode(5), true),
+        (encode(127), encode(-127), true),
+        (encode(0), encode(0), true),
+        // The most negative value is its own negation
+        (encode(-128), encode(-128), true),
+        (encode(5), encode(5), false),
+        (encode(5), 256 - 5 + 256, false),
+        // Values which don't fit in 8 bits should fail
+        (256 + 5, encode(-5), false),
+    ];
+
+    for (value, negated, expected) in test_cases.into_iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), value, negated).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+extern crate dusk_plonk;
+extern crate plonk_gadgets;
+
+use dusk_plonk::prelude::*;
+use plonk_gadgets::{AllocatedScalar, Error as GadgetError, GadgetBuilder};
+
+#[test]
+fn test_gadget_builder() -> Result<(), Error> {
+    // The circuit closure chains a range check, a set membership and a sum through the
+    // builder, returning the constraint report.
+    let circuit = |composer: &mut StandardComposer,
+                   vector: &[u64],
+                   set: &Vec<BlsScalar>|
+     -> Result<Vec<(&'static str, usize)>, GadgetError> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+
+        let mut builder = GadgetBuilder::new(composer);
+        builder
+            .range(vector[0], 8)?
+            .membership(set, vector[1])?
+            .sum(&vector, 100)?;
+        assert_eq!(
+            builder.total_constraints(),
+            builder
+                .constraint_report()
+                .iter()
+                .map(|(_, constraints)| constraints)
+                .sum::<usize>()
+        );
+        Ok(builder.constraint_report().to_vec())
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        vector: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let set: Vec<BlsScalar> = vec![10u64, 20, 30]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            vector: vec![50, 20, 30],
+            expected: true,
+            desc: String::from("All gadgets satisfied"),
+        },
+        TestCase {
+            vector: vec![256, 20, 30],
+            expected: false,
+            desc: String::from("Out of range first element, should fail"),
+        },
+        TestCase {
+            vector: vec![50, 25, 25],
+            expected: false,
+            desc: String::from("Second element not in the set, should fail"),
+        },
+        TestCase {
+            vector: vec![50, 20, 31],
+            expected: false,
+            desc: String::from("Wrong sum, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        let report = circuit(prover.mut_cs(), &case.vector, &set).expect("Gadgets applied");
+        let names: Vec<&str> = report.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, vec!["range", "membership", "sum"]);
+        assert!(report.iter().all(|(_, constraints)| *constraints > 0));
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let verifier_report =
+            circuit(verifier.mut_cs(), &[0, 0, 0], &set).expect("Gadgets applied");
+        // The circuit layout doesn't depend on the witnesses
+        assert_eq!(report, verifier_report);
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,513 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+extern crate dusk_plonk;
+extern crate plonk_gadgets;
+
+use dusk_plonk::prelude::*;
+use plonk_gadgets::{AllocatedScalar, EncodingGadgets::*, Error as GadgetError};
+
+#[test]
+fn test_length_prefixed_bytes_gadget() -> Result<(), Error> {
+    // The circuit closure runs the length_prefixed_bytes gadget, which constraints the bytes
+    // to be a zero-padded byte string of the given length.
+    let circuit =
+        |composer: &mut StandardComposer, length: u64, bytes: &[u64]| -> Result<(), GadgetError> {
+            let length = AllocatedScalar::allocate(composer, BlsScalar::from(length));
+            let bytes: Vec<AllocatedScalar> = bytes
+                .iter()
+                .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+                .collect();
+            length_prefixed_bytes_gadget(composer, length, &bytes, 4)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        length: u64,
+        bytes: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            length: 0,
+            bytes: vec![0, 0, 0, 0],
+            expected: true,
+            desc: String::from("Empty string"),
+        },
+        TestCase {
+            length: 2,
+            bytes: vec![104, 105, 0, 0],
+            expected: true,
+            desc: String::from("Padded string"),
+        },
+        TestCase {
+            length: 4,
+            bytes: vec![255, 0, 1, 33],
+            expected: true,
+            desc: String::from("Full string"),
+        },
+        TestCase {
+            length: 2,
+            bytes: vec![104, 105, 33, 0],
+            expected: false,
+            desc: String::from("Non-zero padding, should fail"),
+        },
+        TestCase {
+            length: 3,
+            bytes: vec![104, 256, 33, 0],
+            expected: false,
+            desc: String::from("Value out of the byte range, should fail"),
+        },
+        TestCase {
+            length: 5,
+            bytes: vec![104, 105, 33, 33],
+            expected: false,
+            desc: String::from("Length over the maximum, should fail"),
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), 0, &[0, 0, 0]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), case.length, &case.bytes).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0, &[0, 0, 0, 0]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_leb128_decode_gadget() -> Result<(), Error> {
+    // The circuit closure runs the leb128_decode gadget, which constraints the value
+    // to be the LEB128 decoding of the bytes.
+    let circuit =
+        |composer: &mut StandardComposer, bytes: &[u64], value: u64| -> Result<(), GadgetError> {
+            let bytes: Vec<AllocatedScalar> = bytes
+                .iter()
+                .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+                .collect();
+            let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+            leb128_decode_gadget(composer, &bytes, value, 32)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        bytes: Vec<u64>,
+        value: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            bytes: vec![0x00],
+            value: 0,
+            expected: true,
+            desc: String::from("Single-byte zero"),
+        },
+        TestCase {
+            bytes: vec![0x7f],
+            value: 127,
+            expected: true,
+            desc: String::from("Single-byte encoding"),
+        },
+        TestCase {
+            bytes: vec![0x7f],
+            value: 128,
+            expected: false,
+            desc: String::from("Wrong single-byte value, should fail"),
+        },
+        TestCase {
+            bytes: vec![0x80, 0x01],
+            value: 128,
+            expected: true,
+            desc: String::from("Two-byte encoding"),
+        },
+        TestCase {
+            bytes: vec![0xe5, 0x8e, 0x26],
+            value: 624_485,
+            expected: true,
+            desc: String::from("Three-byte encoding"),
+        },
+        TestCase {
+            bytes: vec![0x65, 0x8e, 0x26],
+            value: 624_485,
+            expected: false,
+            desc: String::from("Missing continuation bit, should fail"),
+        },
+        TestCase {
+            bytes: vec![0xe5, 0x8e, 0xa6],
+            value: 624_485,
+            expected: false,
+            desc: String::from("Continuation bit on the last byte, should fail"),
+        },
+    ];
+
+    // An empty encoding is rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[], 0).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.bytes, case.value).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![0; case.bytes.len()];
+        assert!(circuit(verifier.mut_cs(), &placeholder, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_rle_consistency_gadget() -> Result<(), Error> {
+    // The circuit closure runs the rle_consistency gadget, which constraints the runs
+    // to expand into the values.
+    let circuit = |composer: &mut StandardComposer,
+                   values: &[u64],
+                   runs: &[(u64, u64)]|
+     -> Result<(), GadgetError> {
+        let values: Vec<AllocatedScalar> = values
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let runs: Vec<(AllocatedScalar, AllocatedScalar)> = runs
+            .iter()
+            .map(|(value, count)| {
+                (
+                    AllocatedScalar::allocate(composer, BlsScalar::from(*value)),
+                    AllocatedScalar::allocate(composer, BlsScalar::from(*count)),
+                )
+            })
+            .collect();
+        rle_consistency_gadget(composer, &values, &runs, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 11, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 10)?;
+
+    struct TestCase {
+        runs: Vec<(u64, u64)>,
+        expected: bool,
+        desc: String,
+    }
+
+    let values = vec![7, 7, 7, 0, 0, 9, 7];
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            runs: vec![(7, 3), (0, 2), (9, 1), (7, 1)],
+            expected: true,
+            desc: String::from("Correct encoding"),
+        },
+        TestCase {
+            runs: vec![(7, 2), (0, 3), (9, 1), (7, 1)],
+            expected: false,
+            desc: String::from("Counts mismatching the values, should fail"),
+        },
+        TestCase {
+            runs: vec![(7, 3), (0, 2), (9, 1), (7, 2)],
+            expected: false,
+            desc: String::from("Counts exceeding the length, should fail"),
+        },
+        TestCase {
+            runs: vec![(7, 3), (0, 2), (9, 2), (7, 0)],
+            expected: false,
+            desc: String::from("Empty run, should fail"),
+        },
+        TestCase {
+            runs: vec![(7, 3), (0, 2), (8, 1), (7, 1)],
+            expected: false,
+            desc: String::from("Wrong run value, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &values, &case.runs).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 7], &[(0, 0); 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_has_prefix_gadget() -> Result<(), Error> {
+    // The circuit closure runs the has_prefix gadget, which constraints the string
+    // to start with the public prefix.
+    let prefix = b"dusk.";
+    let circuit = |composer: &mut StandardComposer, string: &[u8]| -> Result<(), GadgetError> {
+        let bytes: Vec<AllocatedScalar> = string
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x as u64)))
+            .collect();
+        has_prefix_gadget(composer, &bytes, prefix)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    struct TestCase {
+        string: &'static [u8],
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            string: b"dusk.network",
+            expected: true,
+            desc: String::from("Matching prefix"),
+        },
+        TestCase {
+            string: b"dusk.zzzzzzz",
+            expected: true,
+            desc: String::from("Matching prefix with different suffix"),
+        },
+        TestCase {
+            string: b"duck.network",
+            expected: false,
+            desc: String::from("Non-matching prefix, should fail"),
+        },
+        TestCase {
+            string: b"network.dusk",
+            expected: false,
+            desc: String::from("Prefix found elsewhere, should fail"),
+        },
+    ];
+
+    // The prefix can't be longer than the string
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), b"dusk").is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), case.string).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 12]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_is_base64_char_gadget() -> Result<(), Error> {
+    // The circuit closure runs the is_base64_char gadget over a single character.
+    let circuit = |composer: &mut StandardComposer, c: u8| -> Result<(), GadgetError> {
+        let c = AllocatedScalar::allocate(composer, BlsScalar::from(c as u64));
+        is_base64_char_gadget(composer, c)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        c: u8,
+        expected: bool,
+        desc: String,
+    }
+
+    let mut test_cases: Vec<TestCase> = b"AZaz09+/="
+        .iter()
+        .map(|c| TestCase {
+            c: *c,
+            expected: true,
+            desc: format!("Valid character {}", *c as char),
+        })
+        .collect();
+    test_cases.extend(b"-_ \x00".iter().map(|c| TestCase {
+        c: *c,
+        expected: false,
+        desc: format!("Invalid byte {}, should fail", c),
+    }));
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), case.c).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_prefix_free_gadget() -> Result<(), Error> {
+    // The circuit closure runs the prefix_free gadget over codes of up to 4 bits.
+    let circuit =
+        |composer: &mut StandardComposer, codes: &[(u64, u64)]| -> Result<(), GadgetError> {
+            let codes: Vec<(AllocatedScalar, AllocatedScalar)> = codes
+                .iter()
+                .map(|(value, length)| {
+                    (
+                        AllocatedScalar::allocate(composer, BlsScalar::from(*value)),
+                        AllocatedScalar::allocate(composer, BlsScalar::from(*length)),
+                    )
+                })
+                .collect();
+            prefix_free_gadget(composer, &codes, 4)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    struct TestCase {
+        codes: Vec<(u64, u64)>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            // 0, 10, 110, 111
+            codes: vec![(0b0, 1), (0b10, 2), (0b110, 3), (0b111, 3)],
+            expected: true,
+            desc: String::from("Huffman code"),
+        },
+        TestCase {
+            // 00, 01, 1011, 11
+            codes: vec![(0b00, 2), (0b01, 2), (0b1011, 4), (0b11, 2)],
+            expected: true,
+            desc: String::from("Incomplete prefix-free code"),
+        },
+        TestCase {
+            // 0, 10, 101, 111
+            codes: vec![(0b0, 1), (0b10, 2), (0b101, 3), (0b111, 3)],
+            expected: false,
+            desc: String::from("Code prefix of another one, should fail"),
+        },
+        TestCase {
+            // 0, 10, 110, 10
+            codes: vec![(0b0, 1), (0b10, 2), (0b110, 3), (0b10, 2)],
+            expected: false,
+            desc: String::from("Repeated code, should fail"),
+        },
+        TestCase {
+            codes: vec![(0b0, 1), (0b10, 2), (0b110, 3), (0b1110, 3)],
+            expected: false,
+            desc: String::from("Value longer than its length, should fail"),
+        },
+        TestCase {
+            codes: vec![(0b0, 1), (0b10, 2), (0b110, 3), (0b0, 0)],
+            expected: false,
+            desc: String::from("Empty code, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.codes).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[(0, 0); 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
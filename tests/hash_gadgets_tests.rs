@@ -0,0 +1,463 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+extern crate dusk_plonk;
+extern crate plonk_gadgets;
+
+use dusk_plonk::prelude::*;
+use plonk_gadgets::{AllocatedScalar, Error as GadgetError, HashGadgets::*};
+
+#[test]
+fn test_linear_hash_preimage_gadget() -> Result<(), Error> {
+    // The circuit closure runs the linear_hash_preimage gadget, which constraints the
+    // preimage to hash into the digest, set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   preimage: &Vec<BlsScalar>,
+                   digest: BlsScalar,
+                   coeffs: &[BlsScalar]|
+     -> Result<(), GadgetError> {
+        let preimage: Vec<AllocatedScalar> = preimage
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        linear_hash_preimage_gadget(composer, &preimage, digest, coeffs)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let coeffs: Vec<BlsScalar> = vec![7, 13, 42, 1]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+    // 7 * 1 + 13 * 2 + 42 * 3 + 1 * 4 = 163
+    let digest = BlsScalar::from(163);
+
+    struct TestCase {
+        preimage: Vec<BlsScalar>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            preimage: vec![1, 2, 3, 4]
+                .iter()
+                .map(|x| BlsScalar::from(*x))
+                .collect(),
+            expected: true,
+            desc: String::from("Correct preimage, should pass"),
+        },
+        TestCase {
+            preimage: vec![1, 2, 3, 5]
+                .iter()
+                .map(|x| BlsScalar::from(*x))
+                .collect(),
+            expected: false,
+            desc: String::from("Wrong preimage, should fail"),
+        },
+        TestCase {
+            preimage: vec![4, 3, 2, 1]
+                .iter()
+                .map(|x| BlsScalar::from(*x))
+                .collect(),
+            expected: false,
+            desc: String::from("Permuted preimage, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.preimage, digest, &coeffs).is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec().clone();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![BlsScalar::zero(); coeffs.len()];
+        assert!(circuit(verifier.mut_cs(), &placeholder, BlsScalar::zero(), &coeffs).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    // The preimage and the coefficients should have the same length
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &vec![BlsScalar::one()], digest, &coeffs).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_sparse_merkle_membership_gadget() -> Result<(), Error> {
+    // Toy two-to-one hash h(l, r) = l^2 + 3 * r + 7, which is NOT collision-resistant
+    // but distinguishes the order of its inputs.
+    let hash = |l: BlsScalar, r: BlsScalar| l * l + BlsScalar::from(3) * r + BlsScalar::from(7);
+    let hash_gadget = |composer: &mut StandardComposer,
+                       l: AllocatedScalar,
+                       r: AllocatedScalar|
+     -> Result<AllocatedScalar, GadgetError> {
+        let l_sq = composer.mul(BlsScalar::one(), l.var, l.var, BlsScalar::zero(), None);
+        Ok(AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), l_sq),
+                (BlsScalar::from(3), r.var),
+                BlsScalar::from(7),
+                None,
+            ),
+            scalar: hash(l.scalar, r.scalar),
+        })
+    };
+
+    // The circuit closure runs the sparse_merkle_membership gadget against the root,
+    // which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   key: usize,
+                   value: BlsScalar,
+                   path: &[BlsScalar],
+                   root: BlsScalar|
+     -> Result<(), GadgetError> {
+        let key_bits: Vec<AllocatedScalar> = (0..path.len())
+            .map(|i| AllocatedScalar::allocate(composer, BlsScalar::from(((key >> i) & 1) as u64)))
+            .collect();
+        let value = AllocatedScalar::allocate(composer, value);
+        let path: Vec<AllocatedScalar> = path
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        let root_assigned = AllocatedScalar::allocate(composer, root);
+        composer.constrain_to_constant(root_assigned.var, BlsScalar::zero(), Some(-root));
+        sparse_merkle_membership_gadget(
+            composer,
+            &key_bits,
+            value,
+            &path,
+            root_assigned,
+            hash_gadget,
+        )
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // Tree of depth 3 where only the keys 1 and 5 are set, the rest holding the default 0
+    let depth = 3;
+    let mut leaves = vec![BlsScalar::zero(); 1 << depth];
+    leaves[1] = BlsScalar::from(42);
+    leaves[5] = BlsScalar::from(1337);
+    let mut levels = vec![leaves];
+    for _ in 0..depth {
+        let level = levels.last().unwrap();
+        let parents: Vec<BlsScalar> = level.chunks(2).map(|pair| hash(pair[0], pair[1])).collect();
+        levels.push(parents);
+    }
+    let root = levels[depth][0];
+    let path_of =
+        |key: usize| -> Vec<BlsScalar> { (0..depth).map(|i| levels[i][(key >> i) ^ 1]).collect() };
+
+    struct TestCase {
+        key: usize,
+        value: BlsScalar,
+        path: Vec<BlsScalar>,
+        expected: bool,
+        desc: String,
+    }
+
+    let mut tampered_path = path_of(5);
+    tampered_path[1] += BlsScalar::one();
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            key: 5,
+            value: BlsScalar::from(1337),
+            path: path_of(5),
+            expected: true,
+            desc: String::from("Present key, should pass"),
+        },
+        TestCase {
+            key: 2,
+            value: BlsScalar::zero(),
+            path: path_of(2),
+            expected: true,
+            desc: String::from("Absent key holding the default leaf, should pass"),
+        },
+        TestCase {
+            key: 2,
+            value: BlsScalar::from(1337),
+            path: path_of(2),
+            expected: false,
+            desc: String::from("Wrong value for an absent key, should fail"),
+        },
+        TestCase {
+            key: 4,
+            value: BlsScalar::from(1337),
+            path: path_of(5),
+            expected: false,
+            desc: String::from("Value at the wrong key, should fail"),
+        },
+        TestCase {
+            key: 5,
+            value: BlsScalar::from(1337),
+            path: tampered_path,
+            expected: false,
+            desc: String::from("Tampered path, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), case.key, case.value, &case.path, root).is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![BlsScalar::zero(); depth];
+        assert!(circuit(
+            verifier.mut_cs(),
+            0,
+            BlsScalar::zero(),
+            &placeholder,
+            BlsScalar::zero()
+        )
+        .is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    // The path should have one node per key bit
+    let mut prover = Prover::new(b"testing");
+    let value = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::zero());
+    assert!(sparse_merkle_membership_gadget(
+        prover.mut_cs(),
+        &vec![],
+        value,
+        &vec![value],
+        value,
+        hash_gadget
+    )
+    .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_merkle_root_gadget() -> Result<(), Error> {
+    // Same toy two-to-one hash as in the sparse Merkle tree test
+    let hash = |l: BlsScalar, r: BlsScalar| l * l + BlsScalar::from(3) * r + BlsScalar::from(7);
+    let hash_gadget = |composer: &mut StandardComposer,
+                       l: AllocatedScalar,
+                       r: AllocatedScalar|
+     -> Result<AllocatedScalar, GadgetError> {
+        let l_sq = composer.mul(BlsScalar::one(), l.var, l.var, BlsScalar::zero(), None);
+        Ok(AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), l_sq),
+                (BlsScalar::from(3), r.var),
+                BlsScalar::from(7),
+                None,
+            ),
+            scalar: hash(l.scalar, r.scalar),
+        })
+    };
+
+    // Out-of-circuit computation of the root, padding with zero leaves
+    let merkle_root = |leaves: &[u64]| {
+        let mut level: Vec<BlsScalar> = leaves.iter().map(|x| BlsScalar::from(*x)).collect();
+        level.resize(leaves.len().next_power_of_two(), BlsScalar::zero());
+        while level.len() > 1 {
+            level = level.chunks(2).map(|pair| hash(pair[0], pair[1])).collect();
+        }
+        level[0]
+    };
+
+    // The circuit closure runs the merkle_root gadget and constraints the result
+    // to be equal to the root, which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   leaves: &[u64],
+                   root: BlsScalar|
+     -> Result<BlsScalar, GadgetError> {
+        let leaves: Vec<AllocatedScalar> = leaves
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let root_assigned = merkle_root_gadget(composer, &leaves, hash_gadget)?;
+        composer.constrain_to_constant(root_assigned.var, BlsScalar::zero(), Some(-root));
+        Ok(root_assigned.scalar)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    struct TestCase {
+        leaves: Vec<u64>,
+        root: BlsScalar,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            leaves: vec![1, 2, 3, 4, 5, 6, 7, 8],
+            root: merkle_root(&[1, 2, 3, 4, 5, 6, 7, 8]),
+            expected: true,
+            desc: String::from("Power-of-two number of leaves"),
+        },
+        TestCase {
+            leaves: vec![1, 2, 3, 4, 5],
+            root: merkle_root(&[1, 2, 3, 4, 5]),
+            expected: true,
+            desc: String::from("Leaves padded with zeros"),
+        },
+        TestCase {
+            leaves: vec![1, 2, 3, 4, 5, 6, 7, 9],
+            root: merkle_root(&[1, 2, 3, 4, 5, 6, 7, 8]),
+            expected: false,
+            desc: String::from("Tampered leaf, should fail"),
+        },
+        TestCase {
+            leaves: vec![3, 2, 1, 4, 5, 6, 7, 8],
+            root: merkle_root(&[1, 2, 3, 4, 5, 6, 7, 8]),
+            expected: false,
+            desc: String::from("Swapped leaves, should fail"),
+        },
+    ];
+
+    // There must be at least one leaf
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[], BlsScalar::zero()).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        let root = circuit(prover.mut_cs(), &case.leaves, case.root).expect("Merkle root error");
+        assert_eq!(root == case.root, case.expected);
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![0; case.leaves.len()];
+        assert!(circuit(verifier.mut_cs(), &placeholder, BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_accumulator_add_gadget() -> Result<(), Error> {
+    // Same toy two-to-one hash as in the sparse Merkle tree test
+    let hash = |l: BlsScalar, r: BlsScalar| l * l + BlsScalar::from(3) * r + BlsScalar::from(7);
+    let hash_gadget = |composer: &mut StandardComposer,
+                       l: AllocatedScalar,
+                       r: AllocatedScalar|
+     -> Result<AllocatedScalar, GadgetError> {
+        let l_sq = composer.mul(BlsScalar::one(), l.var, l.var, BlsScalar::zero(), None);
+        Ok(AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::one(), l_sq),
+                (BlsScalar::from(3), r.var),
+                BlsScalar::from(7),
+                None,
+            ),
+            scalar: hash(l.scalar, r.scalar),
+        })
+    };
+
+    // The circuit closure chains the updates of the accumulator, whose states
+    // are all private.
+    let circuit = |composer: &mut StandardComposer,
+                   states: &[BlsScalar],
+                   elements: &[u64]|
+     -> Result<(), GadgetError> {
+        let states: Vec<AllocatedScalar> = states
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        for (i, element) in elements.iter().enumerate() {
+            let element = AllocatedScalar::allocate(composer, BlsScalar::from(*element));
+            accumulator_add_gadget(composer, states[i], element, states[i + 1], hash_gadget)?;
+        }
+        Ok(())
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    let elements = [4u64, 8, 15, 16];
+    let mut states = vec![BlsScalar::from(42)];
+    for element in elements.iter() {
+        let last = states[states.len() - 1];
+        states.push(hash(last, BlsScalar::from(*element)));
+    }
+    let mut tampered = states.clone();
+    tampered[2] += BlsScalar::one();
+
+    struct TestCase {
+        states: Vec<BlsScalar>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            states: states.clone(),
+            expected: true,
+            desc: String::from("Chained updates"),
+        },
+        TestCase {
+            states: tampered,
+            expected: false,
+            desc: String::from("Tampered accumulator, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.states, &elements).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[BlsScalar::zero(); 5], &[0; 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
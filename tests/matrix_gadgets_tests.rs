@@ -0,0 +1,341 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+//
+// Copyright (c) DUSK NETWORK. All rights reserved.
+
+extern crate dusk_plonk;
+extern crate plonk_gadgets;
+
+use dusk_plonk::prelude::*;
+use plonk_gadgets::{AllocatedScalar, Error as GadgetError, MatrixGadgets::*};
+
+fn allocate_matrix(
+    composer: &mut StandardComposer,
+    matrix: &[Vec<u64>],
+) -> Vec<Vec<AllocatedScalar>> {
+    matrix
+        .iter()
+        .map(|row| {
+            row.iter()
+                .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn test_apply_permutation_gadget() -> Result<(), Error> {
+    // The circuit closure runs the apply_permutation gadget, which constraints the output to
+    // be the public input permuted by the private permutation matrix.
+    let circuit = |composer: &mut StandardComposer,
+                   perm: &[Vec<u64>],
+                   input: &[BlsScalar],
+                   output: &[u64]|
+     -> Result<(), GadgetError> {
+        let perm = allocate_matrix(composer, perm);
+        let output: Vec<AllocatedScalar> = output
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        apply_permutation_gadget(composer, &perm, input, &output)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let input: Vec<BlsScalar> = vec![10, 20, 30]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    struct TestCase {
+        perm: Vec<Vec<u64>>,
+        output: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            perm: vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]],
+            output: vec![10, 20, 30],
+            expected: true,
+            desc: String::from("Identity permutation, should pass"),
+        },
+        TestCase {
+            perm: vec![vec![0, 0, 1], vec![0, 1, 0], vec![1, 0, 0]],
+            output: vec![30, 20, 10],
+            expected: true,
+            desc: String::from("Reversal permutation, should pass"),
+        },
+        TestCase {
+            perm: vec![vec![0, 0, 1], vec![0, 1, 0], vec![1, 0, 0]],
+            output: vec![10, 20, 30],
+            expected: false,
+            desc: String::from("Output not matching the permutation, should fail"),
+        },
+        TestCase {
+            perm: vec![vec![1, 1, 0], vec![0, 0, 0], vec![0, 0, 1]],
+            output: vec![30, 0, 30],
+            expected: false,
+            desc: String::from("Not a permutation matrix, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.perm, &input, &case.output).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder_perm = vec![vec![0; 3]; 3];
+        assert!(circuit(verifier.mut_cs(), &placeholder_perm, &input, &[0; 3]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // Dimensions must match
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(
+        prover.mut_cs(),
+        &[vec![1, 0], vec![0, 1]],
+        &input,
+        &[10, 20]
+    )
+    .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_is_permutation_matrix_gadget() -> Result<(), Error> {
+    // The circuit closure runs the is_permutation_matrix gadget, which constraints the
+    // private matrix to be a valid permutation matrix.
+    let circuit =
+        |composer: &mut StandardComposer, matrix: &[Vec<u64>]| -> Result<(), GadgetError> {
+            let matrix = allocate_matrix(composer, matrix);
+            is_permutation_matrix_gadget(composer, &matrix)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        matrix: Vec<Vec<u64>>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            matrix: vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]],
+            expected: true,
+            desc: String::from("Identity matrix, should pass"),
+        },
+        TestCase {
+            matrix: vec![vec![0, 1, 0], vec![0, 0, 1], vec![1, 0, 0]],
+            expected: true,
+            desc: String::from("Cyclic permutation, should pass"),
+        },
+        TestCase {
+            matrix: vec![vec![1, 1, 0], vec![0, 0, 0], vec![0, 0, 1]],
+            expected: false,
+            desc: String::from("Two ones in a row, should fail"),
+        },
+        TestCase {
+            matrix: vec![vec![1, 0, 0], vec![1, 0, 0], vec![0, 0, 1]],
+            expected: false,
+            desc: String::from("Two ones in a column, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.matrix).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &vec![vec![0; 3]; 3]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // The matrix must be square
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[vec![1, 0, 0], vec![0, 1, 0]]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_is_symmetric_gadget() -> Result<(), Error> {
+    // The circuit closure runs the is_symmetric gadget, which constraints the
+    // private matrix to be symmetric.
+    let circuit =
+        |composer: &mut StandardComposer, matrix: &[Vec<u64>]| -> Result<(), GadgetError> {
+            let matrix = allocate_matrix(composer, matrix);
+            is_symmetric_gadget(composer, &matrix)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    struct TestCase {
+        matrix: Vec<Vec<u64>>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            matrix: vec![vec![4, 2, 7], vec![2, 5, 1], vec![7, 1, 9]],
+            expected: true,
+            desc: String::from("Symmetric matrix, should pass"),
+        },
+        TestCase {
+            matrix: vec![vec![4, 2, 7], vec![2, 5, 1], vec![7, 3, 9]],
+            expected: false,
+            desc: String::from("Asymmetric matrix, should fail"),
+        },
+        TestCase {
+            matrix: vec![vec![4, 2, 7], vec![2, 5, 1], vec![1, 7, 9]],
+            expected: false,
+            desc: String::from("Transposed entries in a row, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.matrix).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &vec![vec![0; 3]; 3]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // The matrix must be square
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[vec![1, 0, 0], vec![0, 1, 0]]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_matrix_trace_gadget() -> Result<(), Error> {
+    // The circuit closure runs the matrix_trace gadget and constraints the result
+    // to be equal to the trace, which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   matrix: &[Vec<u64>],
+                   trace: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let matrix = allocate_matrix(composer, matrix);
+        let trace_assigned = matrix_trace_gadget(composer, &matrix)?;
+        composer.constrain_to_constant(
+            trace_assigned.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(trace)),
+        );
+        Ok(trace_assigned.scalar)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 6, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 5)?;
+
+    let native_trace =
+        |matrix: &[Vec<u64>]| -> u64 { matrix.iter().enumerate().map(|(i, row)| row[i]).sum() };
+
+    struct TestCase {
+        matrix: Vec<Vec<u64>>,
+        trace: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let matrix = vec![vec![4, 2, 7], vec![3, 5, 1], vec![8, 6, 9]];
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            trace: native_trace(&matrix),
+            matrix: matrix.clone(),
+            expected: true,
+            desc: String::from("Trace of the matrix, should pass"),
+        },
+        TestCase {
+            matrix: vec![vec![1, 0, 0], vec![0, 1, 0], vec![0, 0, 1]],
+            trace: 3,
+            expected: true,
+            desc: String::from("Trace of the identity, should pass"),
+        },
+        TestCase {
+            trace: native_trace(&matrix) + 1,
+            matrix,
+            expected: false,
+            desc: String::from("Wrong trace, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        let trace = circuit(prover.mut_cs(), &case.matrix, case.trace).expect("Trace error");
+        assert_eq!(trace == BlsScalar::from(case.trace), case.expected);
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &vec![vec![0; 3]; 3], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    // The matrix must be square
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[vec![1, 0, 0], vec![0, 1, 0]], 2).is_err());
+
+    Ok(())
+}
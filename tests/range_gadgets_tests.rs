@@ -8,6 +8,7 @@ extern crate dusk_plonk;
 extern crate plonk_gadgets;
 use dusk_plonk::prelude::*;
 use plonk_gadgets::AllocatedScalar;
+use plonk_gadgets::Error as GadgetError;
 use plonk_gadgets::RangeGadgets::*;
 
 fn max_bound_gadget(
@@ -199,3 +200,2358 @@ fn range_check_test() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn fixed_point_decompose_test() -> Result<(), Error> {
+    // The circuit closure decomposes the fixed-point value and constraints the integer and
+    // fractional parts to be equal to the expected ones, which are set as Public Inputs.
+    let circuit = |composer: &mut StandardComposer,
+                   value: BlsScalar,
+                   integer: BlsScalar,
+                   fraction: BlsScalar|
+     -> Result<(), GadgetError> {
+        let value = AllocatedScalar::allocate(composer, value);
+        let (int_part, frac_part) = fixed_point_decompose_gadget(composer, value, 8, 8)?;
+        composer.constrain_to_constant(int_part.var, BlsScalar::zero(), Some(-integer));
+        composer.constrain_to_constant(frac_part.var, BlsScalar::zero(), Some(-fraction));
+        Ok(())
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        value: u64,
+        integer: u64,
+        fraction: u64,
+        expected: bool,
+    }
+    let test_cases = vec![
+        TestCase {
+            value: 5 * 256 + 3,
+            integer: 5,
+            fraction: 3,
+            expected: true,
+        },
+        TestCase {
+            value: 0,
+            integer: 0,
+            fraction: 0,
+            expected: true,
+        },
+        TestCase {
+            value: 255 * 256 + 255,
+            integer: 255,
+            fraction: 255,
+            expected: true,
+        },
+        TestCase {
+            value: 128,
+            integer: 0,
+            fraction: 128,
+            expected: true,
+        },
+        TestCase {
+            // The integer part overflows its 8 bits
+            value: 256 * 256,
+            integer: 256,
+            fraction: 0,
+            expected: false,
+        },
+        TestCase {
+            // The claimed parts don't recompose into the value
+            value: 5 * 256 + 3,
+            integer: 3,
+            fraction: 5,
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(
+            prover.mut_cs(),
+            BlsScalar::from(case.value),
+            BlsScalar::from(case.integer),
+            BlsScalar::from(case.fraction),
+        )
+        .is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec().clone();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(
+            verifier.mut_cs(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            BlsScalar::zero()
+        )
+        .is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    // Widths that can't be represented in a `BlsScalar` should err
+    let mut prover = Prover::default();
+    let value = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::one());
+    assert!(fixed_point_decompose_gadget(prover.mut_cs(), value, 200, 100).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn between_test() -> Result<(), Error> {
+    // The circuit closure runs the between gadget, which constraints the value to lie
+    // strictly between both private bounds.
+    let circuit = |composer: &mut StandardComposer,
+                   value: BlsScalar,
+                   lo: BlsScalar,
+                   hi: BlsScalar|
+     -> Result<(), GadgetError> {
+        let value = AllocatedScalar::allocate(composer, value);
+        let lo = AllocatedScalar::allocate(composer, lo);
+        let hi = AllocatedScalar::allocate(composer, hi);
+        between_gadget(composer, value, lo, hi, 32)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        value: u64,
+        lo: u64,
+        hi: u64,
+        expected: bool,
+    }
+    let test_cases = vec![
+        TestCase {
+            value: 1_600_000_000,
+            lo: 1_500_000_000,
+            hi: 1_700_000_000,
+            expected: true,
+        },
+        TestCase {
+            value: 11,
+            lo: 10,
+            hi: 12,
+            expected: true,
+        },
+        TestCase {
+            value: 10,
+            lo: 10,
+            hi: 20,
+            expected: false,
+        },
+        TestCase {
+            value: 20,
+            lo: 10,
+            hi: 20,
+            expected: false,
+        },
+        TestCase {
+            value: 5,
+            lo: 10,
+            hi: 20,
+            expected: false,
+        },
+        TestCase {
+            value: 25,
+            lo: 10,
+            hi: 20,
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(
+            prover.mut_cs(),
+            BlsScalar::from(case.value),
+            BlsScalar::from(case.lo),
+            BlsScalar::from(case.hi),
+        )
+        .is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(
+            verifier.mut_cs(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            BlsScalar::zero()
+        )
+        .is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn unsigned_width_gadgets_test() -> Result<(), Error> {
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    type WidthGadget = fn(&mut StandardComposer, AllocatedScalar) -> Result<(), GadgetError>;
+
+    struct TestCase {
+        gadget: WidthGadget,
+        value: BlsScalar,
+        expected: bool,
+    }
+
+    let two_pow = |n: u64| BlsScalar::from(2u64).pow(&[n, 0, 0, 0]);
+    let mut test_cases = vec![];
+    for (gadget, bits) in [
+        (u8_gadget as WidthGadget, 8),
+        (u16_gadget as WidthGadget, 16),
+        (u32_gadget as WidthGadget, 32),
+        (u64_gadget as WidthGadget, 64),
+    ]
+    .iter()
+    {
+        // 0 and 2^n - 1 are the boundaries of the range, while 2^n is the first value outside of it
+        test_cases.push(TestCase {
+            gadget: *gadget,
+            value: BlsScalar::zero(),
+            expected: true,
+        });
+        test_cases.push(TestCase {
+            gadget: *gadget,
+            value: two_pow(*bits) - BlsScalar::one(),
+            expected: true,
+        });
+        test_cases.push(TestCase {
+            gadget: *gadget,
+            value: two_pow(*bits),
+            expected: false,
+        });
+        test_cases.push(TestCase {
+            gadget: *gadget,
+            value: -BlsScalar::one(),
+            expected: false,
+        });
+    }
+
+    for case in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        let value = AllocatedScalar::allocate(prover.mut_cs(), case.value);
+        assert!((case.gadget)(prover.mut_cs(), value).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        let value = AllocatedScalar::allocate(verifier.mut_cs(), BlsScalar::zero());
+        assert!((case.gadget)(verifier.mut_cs(), value).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn all_in_range_test() -> Result<(), Error> {
+    // The circuit closure constraints all of the elements in the vector to fit in 16 bits.
+    let circuit =
+        |composer: &mut StandardComposer, vector: &Vec<BlsScalar>| -> Result<(), GadgetError> {
+            let vector: Vec<AllocatedScalar> = vector
+                .iter()
+                .map(|x| AllocatedScalar::allocate(composer, *x))
+                .collect();
+            all_in_range_gadget(composer, &vector, 16)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        vector: Vec<u64>,
+        expected: bool,
+    }
+    let test_cases = vec![
+        TestCase {
+            vector: vec![0, 1, 65_535, 12_000],
+            expected: true,
+        },
+        TestCase {
+            vector: vec![0, 1, 65_536, 12_000],
+            expected: false,
+        },
+        TestCase {
+            vector: vec![70_000, 1, 2, 3],
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        let vector: Vec<BlsScalar> = case.vector.iter().map(|x| BlsScalar::from(*x)).collect();
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), &vector).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &vec![BlsScalar::zero(); vector.len()]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn approx_equal_test() -> Result<(), Error> {
+    // The circuit closure constraints both values to be within a tolerance of 10.
+    let circuit =
+        |composer: &mut StandardComposer, a: BlsScalar, b: BlsScalar| -> Result<(), GadgetError> {
+            let a = AllocatedScalar::allocate(composer, a);
+            let b = AllocatedScalar::allocate(composer, b);
+            approx_equal_gadget(composer, a, b, 10, 16)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        a: u64,
+        b: u64,
+        expected: bool,
+    }
+    let test_cases = vec![
+        TestCase {
+            a: 1000,
+            b: 1000,
+            expected: true,
+        },
+        TestCase {
+            a: 1003,
+            b: 1000,
+            expected: true,
+        },
+        TestCase {
+            a: 1000,
+            b: 1010,
+            expected: true,
+        },
+        TestCase {
+            a: 1010,
+            b: 1000,
+            expected: true,
+        },
+        TestCase {
+            a: 1000,
+            b: 1011,
+            expected: false,
+        },
+        TestCase {
+            a: 1011,
+            b: 1000,
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(
+            prover.mut_cs(),
+            BlsScalar::from(case.a),
+            BlsScalar::from(case.b)
+        )
+        .is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), BlsScalar::zero(), BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn percentile_test() -> Result<(), Error> {
+    // The circuit closure computes the percentile of a vector of 16-bit values.
+    let circuit = |composer: &mut StandardComposer,
+                   vector: &[u64],
+                   p: u64|
+     -> Result<AllocatedScalar, GadgetError> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        percentile_gadget(composer, &vector, p, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    let vector = vec![42, 7, 1000, 7, 13];
+    struct TestCase {
+        p: u64,
+        expected: u64,
+        desc: String,
+    }
+    let test_cases = vec![
+        TestCase {
+            p: 50,
+            expected: 13,
+            desc: String::from("Median"),
+        },
+        TestCase {
+            p: 0,
+            expected: 7,
+            desc: String::from("Minimum"),
+        },
+        TestCase {
+            p: 100,
+            expected: 1000,
+            desc: String::from("Maximum"),
+        },
+    ];
+
+    // A percentile over 100 is rejected
+    let mut prover = Prover::default();
+    assert!(circuit(prover.mut_cs(), &vector, 101).is_err());
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        let percentile = circuit(prover.mut_cs(), &vector, case.p).expect("Valid percentile");
+        assert_eq!(percentile.scalar, BlsScalar::from(case.expected));
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &[0; 5], case.p).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_ok());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn decimal_digits_test() -> Result<(), Error> {
+    // The circuit closure decomposes the value into 4 decimal digits.
+    let circuit = |composer: &mut StandardComposer,
+                   value: BlsScalar|
+     -> Result<Vec<AllocatedScalar>, GadgetError> {
+        let value = AllocatedScalar::allocate(composer, value);
+        decimal_digits_gadget(composer, value, 4, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // 10^6 - 1 doesn't fit in 16 bits
+    let mut prover = Prover::default();
+    let value = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::zero());
+    assert!(decimal_digits_gadget(prover.mut_cs(), value, 6, 16).is_err());
+
+    struct TestCase {
+        value: u64,
+        digits: Vec<u64>,
+        expected: bool,
+    }
+    let test_cases = vec![
+        TestCase {
+            value: 7,
+            digits: vec![7, 0, 0, 0],
+            expected: true,
+        },
+        TestCase {
+            value: 305,
+            digits: vec![5, 0, 3, 0],
+            expected: true,
+        },
+        TestCase {
+            value: 9_999,
+            digits: vec![9, 9, 9, 9],
+            expected: true,
+        },
+        TestCase {
+            value: 10_000,
+            digits: vec![0, 0, 0, 0],
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        let digits = circuit(prover.mut_cs(), BlsScalar::from(case.value)).expect("Valid digits");
+        for (digit, expected_digit) in digits.iter().zip(case.digits.iter()) {
+            assert_eq!(digit.scalar, BlsScalar::from(*expected_digit));
+        }
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn within_window_test() -> Result<(), Error> {
+    // The circuit closure constraints the flag returned for a window of 5 to be equal to
+    // the expected one.
+    let circuit = |composer: &mut StandardComposer,
+                   value: BlsScalar,
+                   target: BlsScalar,
+                   flag: BlsScalar|
+     -> Result<BlsScalar, GadgetError> {
+        let value = AllocatedScalar::allocate(composer, value);
+        let target = AllocatedScalar::allocate(composer, target);
+        let within = within_window_gadget(composer, value, target, 5, 16)?;
+        composer.constrain_to_constant(within.var, flag, None);
+        Ok(within.scalar)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // (value, target, expected flag)
+    let test_cases: Vec<(u64, u64, u64)> = vec![
+        (100, 100, 1),
+        (103, 100, 1),
+        (95, 100, 1),
+        (105, 100, 1),
+        (94, 100, 0),
+        (106, 100, 0),
+        (0, 65_535, 0),
+    ];
+
+    for (value, target, flag) in test_cases.into_iter() {
+        let flag = BlsScalar::from(flag);
+
+        // Proving
+        let mut prover = Prover::default();
+        let within = circuit(
+            prover.mut_cs(),
+            BlsScalar::from(value),
+            BlsScalar::from(target),
+            flag,
+        )
+        .expect("Valid window");
+        assert_eq!(within, flag);
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(
+            verifier.mut_cs(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            flag
+        )
+        .is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_ok());
+
+        // Claiming the opposite flag should fail
+        let wrong_flag = BlsScalar::one() - flag;
+        let mut prover = Prover::default();
+        assert!(circuit(
+            prover.mut_cs(),
+            BlsScalar::from(value),
+            BlsScalar::from(target),
+            wrong_flag,
+        )
+        .is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::default();
+        assert!(circuit(
+            verifier.mut_cs(),
+            BlsScalar::zero(),
+            BlsScalar::zero(),
+            wrong_flag
+        )
+        .is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn not_expired_test() -> Result<(), Error> {
+    // The circuit closure checks a credential valid for 100 units of time, where the
+    // current timestamp is set as Public Input.
+    let circuit =
+        |composer: &mut StandardComposer, issued_at: u64, now: u64| -> Result<(), GadgetError> {
+            let issued_at = AllocatedScalar::allocate(composer, BlsScalar::from(issued_at));
+            let now_assigned = AllocatedScalar::allocate(composer, BlsScalar::from(now));
+            composer.constrain_to_constant(
+                now_assigned.var,
+                BlsScalar::zero(),
+                Some(-BlsScalar::from(now)),
+            );
+            not_expired_gadget(composer, issued_at, 100, now_assigned, 32)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        now: u64,
+        expected: bool,
+        desc: String,
+    }
+    let issued_at = 1_600_000_000;
+    let test_cases = vec![
+        TestCase {
+            now: issued_at,
+            expected: true,
+            desc: String::from("Issuance timestamp"),
+        },
+        TestCase {
+            now: issued_at + 50,
+            expected: true,
+            desc: String::from("Within the window"),
+        },
+        TestCase {
+            now: issued_at + 99,
+            expected: true,
+            desc: String::from("Last valid timestamp"),
+        },
+        TestCase {
+            now: issued_at + 100,
+            expected: false,
+            desc: String::from("Expired, should fail"),
+        },
+        TestCase {
+            now: issued_at - 1,
+            expected: false,
+            desc: String::from("Not yet valid, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), issued_at, case.now).is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn monotone_nondecreasing_test() -> Result<(), Error> {
+    // The circuit closure constraints the snapshots of a 32-bit counter to never decrease.
+    let circuit = |composer: &mut StandardComposer, snapshots: &[u64]| -> Result<(), GadgetError> {
+        let snapshots: Vec<AllocatedScalar> = snapshots
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        monotone_nondecreasing_gadget(composer, &snapshots, 32)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // A decreasing sequence is rejected
+    let mut prover = Prover::default();
+    assert!(circuit(prover.mut_cs(), &[1, 5, 4, 9]).is_err());
+
+    for snapshots in [vec![1, 5, 5, 9], vec![1, 2, 3, 4], vec![7, 7, 7, 7]].iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), snapshots).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &[0; 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_ok());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn lcg_step_test() -> Result<(), Error> {
+    // Parameters of the classic ANSI C linear congruential generator
+    const A: u64 = 1_103_515_245;
+    const C: u64 = 12_345;
+    const M: u64 = 1 << 31;
+
+    // The circuit closure constraints a chain of LCG steps between the provided states.
+    let circuit = |composer: &mut StandardComposer, states: &[u64]| -> Result<(), GadgetError> {
+        let states: Vec<AllocatedScalar> = states
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        for pair in states.windows(2) {
+            lcg_step_gadget(composer, pair[0], A, C, M, pair[1], 64)?;
+        }
+        Ok(())
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 11, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 10)?;
+
+    let mut states = vec![42u64];
+    for _ in 0..3 {
+        let state = *states.last().unwrap();
+        states.push((A * state + C) % M);
+    }
+    let mut tampered = states.clone();
+    tampered[2] += 1;
+
+    for (states, expected) in [(states, true), (tampered, false)].iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), states).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &[0; 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        if *expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn isqrt_test() -> Result<(), Error> {
+    // The circuit closure computes the integer square root of a 32-bit value.
+    let circuit = |composer: &mut StandardComposer,
+                   value: BlsScalar|
+     -> Result<AllocatedScalar, GadgetError> {
+        let value = AllocatedScalar::allocate(composer, value);
+        isqrt_gadget(composer, value, 32)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // (value, expected root)
+    let test_cases: Vec<(u64, u64)> = vec![
+        (0, 0),
+        (1, 1),
+        (144, 12),
+        (4_294_836_225, 65_535),
+        (2, 1),
+        (150, 12),
+        (4_294_967_295, 65_535),
+    ];
+
+    for (value, expected_root) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        let root = circuit(prover.mut_cs(), BlsScalar::from(value)).expect("Valid value");
+        assert_eq!(root.scalar, BlsScalar::from(expected_root));
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_ok());
+    }
+
+    // A value which doesn't fit in 32 bits should fail
+    let mut prover = Prover::default();
+    assert!(circuit(prover.mut_cs(), BlsScalar::from(1u64 << 33)).is_ok());
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::default();
+    assert!(circuit(verifier.mut_cs(), BlsScalar::zero()).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier
+        .verify(&proof, &vk, &vec![BlsScalar::zero()])
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn piecewise_linear_test() -> Result<(), Error> {
+    // The circuit closure evaluates a tiered price with three segments:
+    // y = 10x below 100, y = 8x + 200 in [100, 1000) and y = 5x + 3200 from 1000 on.
+    let circuit =
+        |composer: &mut StandardComposer, x: BlsScalar, y: BlsScalar| -> Result<(), GadgetError> {
+            let x = AllocatedScalar::allocate(composer, x);
+            let y = AllocatedScalar::allocate(composer, y);
+            piecewise_linear_gadget(
+                composer,
+                x,
+                &[100, 1000],
+                &[10, 8, 5],
+                &[0, 200, 3200],
+                y,
+                16,
+            )
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        x: u64,
+        y: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            x: 0,
+            y: 0,
+            expected: true,
+            desc: String::from("Lower end of the first segment"),
+        },
+        TestCase {
+            x: 42,
+            y: 420,
+            expected: true,
+            desc: String::from("Within the first segment"),
+        },
+        TestCase {
+            x: 100,
+            y: 1000,
+            expected: true,
+            desc: String::from("At the first breakpoint"),
+        },
+        TestCase {
+            x: 500,
+            y: 4200,
+            expected: true,
+            desc: String::from("Within the second segment"),
+        },
+        TestCase {
+            x: 1000,
+            y: 8200,
+            expected: true,
+            desc: String::from("At the second breakpoint"),
+        },
+        TestCase {
+            x: 4000,
+            y: 23200,
+            expected: true,
+            desc: String::from("Within the last segment"),
+        },
+        TestCase {
+            x: 500,
+            y: 5000,
+            expected: false,
+            desc: String::from("Value of the wrong segment, should fail"),
+        },
+        TestCase {
+            x: 1000,
+            y: 8000,
+            expected: false,
+            desc: String::from("Value of the previous segment at a breakpoint, should fail"),
+        },
+    ];
+
+    // Inconsistent parameters are rejected
+    let mut prover = Prover::default();
+    let x = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::one());
+    assert!(piecewise_linear_gadget(prover.mut_cs(), x, &[10], &[1, 2], &[1], x, 16).is_err());
+    assert!(
+        piecewise_linear_gadget(prover.mut_cs(), x, &[10, 10], &[1, 2, 3], &[1, 2, 3], x, 16)
+            .is_err()
+    );
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(
+            prover.mut_cs(),
+            BlsScalar::from(case.x),
+            BlsScalar::from(case.y)
+        )
+        .is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), BlsScalar::zero(), BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn rank_in_public_test() -> Result<(), Error> {
+    // The circuit closure constraints the rank of the value within the public list
+    // to be equal to the expected one, which is set as Public Input.
+    let sorted_public: Vec<BlsScalar> = vec![10u64, 20, 20, 35, 50]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+    let circuit = |composer: &mut StandardComposer,
+                   value: u64,
+                   rank: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+        let rank_assigned = rank_in_public_gadget(composer, value, &sorted_public, 8)?;
+        composer.constrain_to_constant(
+            rank_assigned.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(rank)),
+        );
+        Ok(rank_assigned.scalar)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // (value, rank)
+    let test_cases: Vec<(u64, u64)> = vec![
+        (0, 0),
+        (10, 0),
+        (11, 1),
+        (20, 1),
+        (21, 3),
+        (50, 4),
+        (255, 5),
+    ];
+
+    for (value, rank) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        let result = circuit(prover.mut_cs(), value, rank).expect("Rank error");
+        assert_eq!(result, BlsScalar::from(rank));
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+    }
+
+    // A wrong rank should fail
+    let mut prover = Prover::default();
+    assert!(circuit(prover.mut_cs(), 21, 2).is_ok());
+    let pi = prover.mut_cs().construct_dense_pi_vec();
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::default();
+    assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier.verify(&proof, &vk, &pi).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn crt_consistency_test() -> Result<(), Error> {
+    // The circuit closure checks the residues of the value modulo 3, 5 and 7.
+    let moduli = [3u64, 5, 7];
+    let circuit = |composer: &mut StandardComposer,
+                   value: u64,
+                   residues: &[u64]|
+     -> Result<(), GadgetError> {
+        let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+        let residues: Vec<(u64, AllocatedScalar)> = moduli
+            .iter()
+            .zip(residues.iter())
+            .map(|(m, r)| (*m, AllocatedScalar::allocate(composer, BlsScalar::from(*r))))
+            .collect();
+        crt_consistency_gadget(composer, value, &residues, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        value: u64,
+        residues: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            value: 1000,
+            residues: vec![1, 0, 6],
+            expected: true,
+            desc: String::from("Consistent residues"),
+        },
+        TestCase {
+            value: 104,
+            residues: vec![2, 4, 6],
+            expected: true,
+            desc: String::from("Consistent residues below the product of the moduli"),
+        },
+        TestCase {
+            value: 1000,
+            residues: vec![2, 0, 6],
+            expected: false,
+            desc: String::from("Inconsistent residue, should fail"),
+        },
+        TestCase {
+            value: 1000,
+            residues: vec![1, 5, 6],
+            expected: false,
+            desc: String::from("Residue congruent but not reduced, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), case.value, &case.residues).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, &[0; 3]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn pack_fields_test() -> Result<(), Error> {
+    // The circuit closure packs the high and low fields, the latter within 12 bits.
+    let circuit = |composer: &mut StandardComposer,
+                   high: u64,
+                   low: u64,
+                   packed: u64|
+     -> Result<(), GadgetError> {
+        let high = AllocatedScalar::allocate(composer, BlsScalar::from(high));
+        let low = AllocatedScalar::allocate(composer, BlsScalar::from(low));
+        let packed = AllocatedScalar::allocate(composer, BlsScalar::from(packed));
+        pack_fields_gadget(composer, high, low, 12, packed)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        high: u64,
+        low: u64,
+        packed: u64,
+        expected: bool,
+    }
+    let test_cases = vec![
+        TestCase {
+            high: 0xab,
+            low: 0xcde,
+            packed: 0xabcde,
+            expected: true,
+        },
+        TestCase {
+            high: 0,
+            low: 0,
+            packed: 0,
+            expected: true,
+        },
+        TestCase {
+            high: 1,
+            low: 0xfff,
+            packed: 0x1fff,
+            expected: true,
+        },
+        TestCase {
+            high: 0xab,
+            low: 0xcde,
+            packed: 0xabcdf,
+            expected: false,
+        },
+        // A low field exceeding its width which still recomposes into the packed value
+        TestCase {
+            high: 0xaa,
+            low: 0x1cde,
+            packed: 0xabcde,
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), case.high, case.low, case.packed).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn unpack_fields_test() -> Result<(), Error> {
+    // The circuit closure unpacks the value into fields of the given widths and constraints
+    // them to be equal to the expected ones, which are set as Public Inputs.
+    let circuit = |composer: &mut StandardComposer,
+                   packed: u64,
+                   widths: &[u32],
+                   fields: &[u64]|
+     -> Result<Vec<BlsScalar>, GadgetError> {
+        let packed = AllocatedScalar::allocate(composer, BlsScalar::from(packed));
+        let unpacked = unpack_fields_gadget(composer, packed, widths)?;
+        for (field, expected) in unpacked.iter().zip(fields.iter()) {
+            composer.constrain_to_constant(
+                field.var,
+                BlsScalar::zero(),
+                Some(-BlsScalar::from(*expected)),
+            );
+        }
+        Ok(unpacked.iter().map(|field| field.scalar).collect())
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // (packed, widths, fields)
+    let test_cases: Vec<(u64, Vec<u32>, Vec<u64>)> = vec![
+        (0xabcde, vec![12, 8], vec![0xcde, 0xab]),
+        (0b1011_0110, vec![1, 3, 4], vec![0, 0b011, 0b1011]),
+        (0xdead_beef, vec![16, 16], vec![0xbeef, 0xdead]),
+        (
+            0x1234_5678_9abc,
+            vec![8, 16, 8, 16],
+            vec![0xbc, 0x789a, 0x56, 0x1234],
+        ),
+    ];
+
+    for (packed, widths, fields) in test_cases.iter() {
+        // Proving
+        let mut prover = Prover::default();
+        let unpacked = circuit(prover.mut_cs(), *packed, widths, fields).expect("Unpacking error");
+        let expected: Vec<BlsScalar> = fields.iter().map(|x| BlsScalar::from(*x)).collect();
+        assert_eq!(unpacked, expected);
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, widths, &vec![0; fields.len()]).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+    }
+
+    // A value which doesn't fit in the total width should fail
+    let mut prover = Prover::default();
+    assert!(circuit(prover.mut_cs(), 0x1abcde, &[12, 8], &[0xcde, 0xab]).is_ok());
+    let pi = prover.mut_cs().construct_dense_pi_vec();
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::default();
+    assert!(circuit(verifier.mut_cs(), 0, &[12, 8], &[0, 0]).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier.verify(&proof, &vk, &pi).is_err());
+
+    // An overflowing total width is rejected
+    let mut prover = Prover::default();
+    assert!(circuit(prover.mut_cs(), 0, &[128, 127], &[0, 0]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn luhn_valid_test() -> Result<(), Error> {
+    // The circuit closure checks the Luhn checksum of a 16-digit card number.
+    let circuit = |composer: &mut StandardComposer, number: &str| -> Result<(), GadgetError> {
+        let digits: Vec<AllocatedScalar> = number
+            .chars()
+            .map(|c| {
+                let digit = c.to_digit(10).expect("Not a digit") as u64;
+                AllocatedScalar::allocate(composer, BlsScalar::from(digit))
+            })
+            .collect();
+        luhn_valid_gadget(composer, &digits)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 11, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 10)?;
+
+    let test_cases = vec![
+        ("4539578763621486", true),
+        ("4111111111111111", true),
+        ("5555555555554444", true),
+        ("0000000000000000", true),
+        // Wrong check digit
+        ("4539578763621487", false),
+        // Swapped digits
+        ("4111111111111161", false),
+    ];
+
+    for (number, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), number).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), "0000000000000000").is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // A digit out of [0, 10) should fail, even if the checksum matches
+    let mut prover = Prover::default();
+    let mut digits: Vec<AllocatedScalar> = (0..15)
+        .map(|_| AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::zero()))
+        .collect();
+    digits.push(AllocatedScalar::allocate(
+        prover.mut_cs(),
+        BlsScalar::from(10),
+    ));
+    assert!(luhn_valid_gadget(prover.mut_cs(), &digits).is_ok());
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::default();
+    let digits: Vec<AllocatedScalar> = (0..16)
+        .map(|_| AllocatedScalar::allocate(verifier.mut_cs(), BlsScalar::zero()))
+        .collect();
+    assert!(luhn_valid_gadget(verifier.mut_cs(), &digits).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier
+        .verify(&proof, &vk, &vec![BlsScalar::zero()])
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn valid_date_test() -> Result<(), Error> {
+    // The circuit closure checks the date to be valid, with years of up to 16 bits.
+    let circuit = |composer: &mut StandardComposer,
+                   year: u64,
+                   month: u64,
+                   day: u64|
+     -> Result<(), GadgetError> {
+        let year = AllocatedScalar::allocate(composer, BlsScalar::from(year));
+        let month = AllocatedScalar::allocate(composer, BlsScalar::from(month));
+        let day = AllocatedScalar::allocate(composer, BlsScalar::from(day));
+        valid_date_gadget(composer, year, month, day, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    // (year, month, day, expected)
+    let test_cases = vec![
+        (1990, 7, 14, true),
+        (2021, 1, 1, true),
+        (2021, 12, 31, true),
+        (2021, 4, 30, true),
+        (2024, 2, 29, true),
+        (2000, 2, 29, true),
+        (2023, 2, 28, true),
+        (2023, 2, 29, false),
+        (1900, 2, 29, false),
+        (2021, 4, 31, false),
+        (2021, 1, 0, false),
+        (2021, 0, 10, false),
+        (2021, 13, 10, false),
+    ];
+
+    for (year, month, day, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), year, month, day).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn saturating_add_test() -> Result<(), Error> {
+    // The circuit closure constraints the saturated sum of both 16-bit values
+    // to be equal to the claimed one, which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   a: u64,
+                   b: u64,
+                   claimed: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let a = AllocatedScalar::allocate(composer, BlsScalar::from(a));
+        let b = AllocatedScalar::allocate(composer, BlsScalar::from(b));
+        let sum = saturating_add_gadget(composer, a, b, 1000, 16)?;
+        composer.constrain_to_constant(sum.var, BlsScalar::zero(), Some(-BlsScalar::from(claimed)));
+        Ok(sum.scalar)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        a: u64,
+        b: u64,
+        claimed: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            a: 200,
+            b: 300,
+            claimed: 500,
+            expected: true,
+            desc: String::from("Sum below the bound"),
+        },
+        TestCase {
+            a: 600,
+            b: 400,
+            claimed: 1000,
+            expected: true,
+            desc: String::from("Sum equal to the bound"),
+        },
+        TestCase {
+            a: 900,
+            b: 800,
+            claimed: 1000,
+            expected: true,
+            desc: String::from("Sum above the bound gets saturated"),
+        },
+        TestCase {
+            a: 65535,
+            b: 65535,
+            claimed: 1000,
+            expected: true,
+            desc: String::from("Largest 16-bit values get saturated"),
+        },
+        TestCase {
+            a: 900,
+            b: 800,
+            claimed: 1700,
+            expected: false,
+            desc: String::from("Unsaturated sum above the bound, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        let sum =
+            circuit(prover.mut_cs(), case.a, case.b, case.claimed).expect("Saturating add error");
+        assert_eq!(sum == BlsScalar::from(case.claimed), case.expected);
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn clamp_test() -> Result<(), Error> {
+    // The circuit closure constraints the 16-bit value clamped into [100, 1000]
+    // to be equal to the claimed one, which is set as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   value: u64,
+                   claimed: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+        let clamped = clamp_gadget(composer, value, 100, 1000, 16)?;
+        composer.constrain_to_constant(
+            clamped.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(claimed)),
+        );
+        Ok(clamped.scalar)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        value: u64,
+        claimed: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            value: 7,
+            claimed: 100,
+            expected: true,
+            desc: String::from("Value below lo"),
+        },
+        TestCase {
+            value: 100,
+            claimed: 100,
+            expected: true,
+            desc: String::from("Value equal to lo"),
+        },
+        TestCase {
+            value: 512,
+            claimed: 512,
+            expected: true,
+            desc: String::from("Value inside the range"),
+        },
+        TestCase {
+            value: 1000,
+            claimed: 1000,
+            expected: true,
+            desc: String::from("Value equal to hi"),
+        },
+        TestCase {
+            value: 65535,
+            claimed: 1000,
+            expected: true,
+            desc: String::from("Value above hi"),
+        },
+        TestCase {
+            value: 7,
+            claimed: 7,
+            expected: false,
+            desc: String::from("Unclamped value below lo, should fail"),
+        },
+        TestCase {
+            value: 2000,
+            claimed: 2000,
+            expected: false,
+            desc: String::from("Unclamped value above hi, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        let clamped = circuit(prover.mut_cs(), case.value, case.claimed).expect("Clamp error");
+        assert_eq!(clamped == BlsScalar::from(case.claimed), case.expected);
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    // The bounds must be ordered
+    let mut prover = Prover::default();
+    let value = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::from(5u64));
+    assert!(clamp_gadget(prover.mut_cs(), value, 10, 1, 16).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn stack_consistency_test() -> Result<(), Error> {
+    // The circuit closure runs the stack consistency gadget over the trace of
+    // (is_push, value) operations, with depths fitting in 8 bits.
+    let circuit = |composer: &mut StandardComposer,
+                   ops: &[(u64, u64)],
+                   final_depth: u64|
+     -> Result<(), GadgetError> {
+        let ops: Vec<(AllocatedScalar, AllocatedScalar)> = ops
+            .iter()
+            .map(|(is_push, value)| {
+                (
+                    AllocatedScalar::allocate(composer, BlsScalar::from(*is_push)),
+                    AllocatedScalar::allocate(composer, BlsScalar::from(*value)),
+                )
+            })
+            .collect();
+        let final_depth = AllocatedScalar::allocate(composer, BlsScalar::from(final_depth));
+        stack_consistency_gadget(composer, &ops, final_depth, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        ops: Vec<(u64, u64)>,
+        final_depth: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            ops: vec![(1, 4), (1, 7), (0, 7), (1, 9), (0, 9), (0, 4)],
+            final_depth: 0,
+            expected: true,
+            desc: String::from("Balanced sequence"),
+        },
+        TestCase {
+            ops: vec![(1, 4), (1, 7), (1, 9), (0, 9), (1, 2), (0, 2)],
+            final_depth: 2,
+            expected: true,
+            desc: String::from("Sequence leaving two elements on the stack"),
+        },
+        TestCase {
+            ops: vec![(1, 4), (1, 7), (0, 7), (1, 9), (0, 9), (0, 4)],
+            final_depth: 1,
+            expected: false,
+            desc: String::from("Wrong final depth, should fail"),
+        },
+        TestCase {
+            ops: vec![(1, 4), (0, 4), (0, 0), (1, 3), (1, 5), (0, 5)],
+            final_depth: 0,
+            expected: false,
+            desc: String::from("Underflow, should fail"),
+        },
+        TestCase {
+            ops: vec![(1, 4), (2, 7), (0, 7), (0, 4), (1, 1), (0, 1)],
+            final_depth: 2,
+            expected: false,
+            desc: String::from("Non-bit operation, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), &case.ops, case.final_depth).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &[(1, 0); 6], 6).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn probability_distribution_test() -> Result<(), Error> {
+    // The circuit closure constraints the probabilities to form a distribution
+    // with 1.0 represented as 10000, i.e. four decimal digits of precision.
+    let circuit = |composer: &mut StandardComposer, probs: &[u64]| -> Result<(), GadgetError> {
+        let probs: Vec<AllocatedScalar> = probs
+            .iter()
+            .map(|p| AllocatedScalar::allocate(composer, BlsScalar::from(*p)))
+            .collect();
+        probability_distribution_gadget(composer, &probs, 10_000, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    struct TestCase {
+        probs: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            probs: vec![2500, 1250, 6250, 0],
+            expected: true,
+            desc: String::from("Valid distribution"),
+        },
+        TestCase {
+            probs: vec![0, 10_000, 0, 0],
+            expected: true,
+            desc: String::from("Degenerate distribution"),
+        },
+        TestCase {
+            probs: vec![2500, 1250, 6249, 0],
+            expected: false,
+            desc: String::from("Distribution adding up to less than one, should fail"),
+        },
+        TestCase {
+            probs: vec![2500, 1250, 6250, 1],
+            expected: false,
+            desc: String::from("Distribution adding up to more than one, should fail"),
+        },
+        TestCase {
+            probs: vec![12_000, 0, 0, 0],
+            expected: false,
+            desc: String::from("Probability above one, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), &case.probs).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &[0; 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn within_variance_bound_test() -> Result<(), Error> {
+    // The circuit closure constraints the variance of the 8-bit values around the
+    // mean to be at most 4.
+    let circuit =
+        |composer: &mut StandardComposer, values: &[u64], mean: u64| -> Result<(), GadgetError> {
+            let values: Vec<AllocatedScalar> = values
+                .iter()
+                .map(|v| AllocatedScalar::allocate(composer, BlsScalar::from(*v)))
+                .collect();
+            let mean = AllocatedScalar::allocate(composer, BlsScalar::from(mean));
+            within_variance_bound_gadget(composer, &values, mean, 4, 8)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        values: Vec<u64>,
+        mean: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            values: vec![100, 101, 99, 102, 98],
+            mean: 100,
+            expected: true,
+            desc: String::from("Tight cluster"),
+        },
+        TestCase {
+            values: vec![100, 104, 100, 100, 98],
+            mean: 100,
+            expected: true,
+            desc: String::from("Variance equal to the bound"),
+        },
+        TestCase {
+            values: vec![100, 104, 101, 100, 98],
+            mean: 100,
+            expected: false,
+            desc: String::from("Variance just above the bound, should fail"),
+        },
+        TestCase {
+            values: vec![0, 255, 10, 200, 35],
+            mean: 100,
+            expected: false,
+            desc: String::from("Spread-out values, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), &case.values, case.mean).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &[0; 5], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn token_bucket_test() -> Result<(), Error> {
+    // The circuit closure constraints the transition of a bucket holding up to 100 tokens,
+    // which is refilled with 10 tokens and charged 30 tokens per operation.
+    let circuit = |composer: &mut StandardComposer,
+                   prev_tokens: u64,
+                   new_tokens: u64|
+     -> Result<(), GadgetError> {
+        let prev_tokens = AllocatedScalar::allocate(composer, BlsScalar::from(prev_tokens));
+        let new_tokens = AllocatedScalar::allocate(composer, BlsScalar::from(new_tokens));
+        token_bucket_gadget(composer, prev_tokens, 10, 30, 100, new_tokens, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        prev_tokens: u64,
+        new_tokens: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            prev_tokens: 50,
+            new_tokens: 30,
+            expected: true,
+            desc: String::from("Allowed operation"),
+        },
+        TestCase {
+            prev_tokens: 95,
+            new_tokens: 70,
+            expected: true,
+            desc: String::from("Allowed operation with a full bucket"),
+        },
+        TestCase {
+            prev_tokens: 20,
+            new_tokens: 0,
+            expected: true,
+            desc: String::from("Allowed operation emptying the bucket"),
+        },
+        TestCase {
+            prev_tokens: 95,
+            new_tokens: 75,
+            expected: false,
+            desc: String::from("Refill above the bucket size, should fail"),
+        },
+        TestCase {
+            prev_tokens: 50,
+            new_tokens: 40,
+            expected: false,
+            desc: String::from("Wrong new balance, should fail"),
+        },
+        TestCase {
+            prev_tokens: 15,
+            new_tokens: 0,
+            expected: false,
+            desc: String::from("Operation exceeding the bucket, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), case.prev_tokens, case.new_tokens).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // An operation exceeding the bucket can't be hidden by wrapping the new balance
+    let mut prover = Prover::default();
+    let prev_tokens = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::from(15));
+    let new_tokens =
+        AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::from(25) - BlsScalar::from(30));
+    assert!(token_bucket_gadget(prover.mut_cs(), prev_tokens, 10, 30, 100, new_tokens, 16).is_ok());
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::default();
+    assert!(circuit(verifier.mut_cs(), 0, 0).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier
+        .verify(&proof, &vk, &vec![BlsScalar::zero()])
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn non_overlapping_intervals_test() -> Result<(), Error> {
+    // The circuit closure constraints the 8-bit intervals to be non-overlapping.
+    let circuit =
+        |composer: &mut StandardComposer, intervals: &[(u64, u64)]| -> Result<(), GadgetError> {
+            let intervals: Vec<(AllocatedScalar, AllocatedScalar)> = intervals
+                .iter()
+                .map(|(start, end)| {
+                    (
+                        AllocatedScalar::allocate(composer, BlsScalar::from(*start)),
+                        AllocatedScalar::allocate(composer, BlsScalar::from(*end)),
+                    )
+                })
+                .collect();
+            non_overlapping_intervals_gadget(composer, &intervals, 8)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    struct TestCase {
+        intervals: Vec<(u64, u64)>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            intervals: vec![(9, 10), (12, 14), (15, 18), (20, 21)],
+            expected: true,
+            desc: String::from("Non-overlapping intervals"),
+        },
+        TestCase {
+            intervals: vec![(9, 10), (10, 14), (14, 14), (14, 21)],
+            expected: true,
+            desc: String::from("Adjacent and empty intervals"),
+        },
+        TestCase {
+            intervals: vec![(9, 10), (12, 16), (15, 18), (20, 21)],
+            expected: false,
+            desc: String::from("Overlapping intervals, should fail"),
+        },
+        TestCase {
+            intervals: vec![(9, 10), (12, 14), (18, 15), (20, 21)],
+            expected: false,
+            desc: String::from("Malformed interval, should fail"),
+        },
+        TestCase {
+            intervals: vec![(12, 14), (9, 10), (15, 18), (20, 21)],
+            expected: false,
+            desc: String::from("Unsorted intervals, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), &case.intervals).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &[(0, 0); 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn bounded_gap_test() -> Result<(), Error> {
+    // The circuit closure constraints the consecutive gaps of the sorted 8-bit
+    // vector to be at most `max_gap`.
+    let circuit = |composer: &mut StandardComposer,
+                   sorted: &[u64],
+                   max_gap: u64|
+     -> Result<(), GadgetError> {
+        let sorted: Vec<AllocatedScalar> = sorted
+            .iter()
+            .map(|v| AllocatedScalar::allocate(composer, BlsScalar::from(*v)))
+            .collect();
+        bounded_gap_gadget(composer, &sorted, max_gap, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        sorted: Vec<u64>,
+        max_gap: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            sorted: vec![10, 12, 12, 15, 19],
+            max_gap: 5,
+            expected: true,
+            desc: String::from("Tight cluster"),
+        },
+        TestCase {
+            sorted: vec![10, 15, 20, 25, 30],
+            max_gap: 5,
+            expected: true,
+            desc: String::from("Gaps equal to the bound"),
+        },
+        TestCase {
+            sorted: vec![10, 12, 18, 20, 21],
+            max_gap: 5,
+            expected: false,
+            desc: String::from("Large gap, should fail"),
+        },
+        TestCase {
+            sorted: vec![10, 12, 11, 15, 19],
+            max_gap: 5,
+            expected: false,
+            desc: String::from("Unsorted vector, should fail"),
+        },
+        TestCase {
+            sorted: vec![10, 10, 12, 200, 255],
+            max_gap: 1_000,
+            expected: true,
+            desc: String::from("Bound above the bit length"),
+        },
+        TestCase {
+            sorted: vec![10, 12, 11, 200, 255],
+            max_gap: 1_000,
+            expected: false,
+            desc: String::from("Bound above the bit length, unsorted vector, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), &case.sorted, case.max_gap).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), &[0; 5], case.max_gap).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn mod_exp_test() -> Result<(), Error> {
+    // The circuit closure constraints the result of raising the 16-bit base to the
+    // 6-bit exponent modulo 97.
+    let circuit = |composer: &mut StandardComposer,
+                   base: u64,
+                   exp: u64,
+                   result: u64|
+     -> Result<(), GadgetError> {
+        let base = AllocatedScalar::allocate(composer, BlsScalar::from(base));
+        let exp_bits: Vec<AllocatedScalar> = (0..6)
+            .map(|i| AllocatedScalar::allocate(composer, BlsScalar::from((exp >> i) & 1)))
+            .collect();
+        let result = AllocatedScalar::allocate(composer, BlsScalar::from(result));
+        mod_exp_gadget(composer, base, &exp_bits, 97, result, 16)
+    };
+
+    // Native modular exponentiation by repeated multiplication
+    let mod_exp = |base: u64, exp: u64, modulus: u64| -> u64 {
+        (0..exp).fold(1, |acc, _| acc * base % modulus)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 11, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 10)?;
+
+    struct TestCase {
+        base: u64,
+        exp: u64,
+        result: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            base: 5,
+            exp: 0,
+            result: 1,
+            expected: true,
+            desc: String::from("Zero exponent"),
+        },
+        TestCase {
+            base: 5,
+            exp: 3,
+            result: mod_exp(5, 3, 97),
+            expected: true,
+            desc: String::from("Small exponent"),
+        },
+        TestCase {
+            base: 1234,
+            exp: 45,
+            result: mod_exp(1234, 45, 97),
+            expected: true,
+            desc: String::from("Base larger than the modulus"),
+        },
+        TestCase {
+            base: 96,
+            exp: 63,
+            result: mod_exp(96, 63, 97),
+            expected: true,
+            desc: String::from("All exponent bits set"),
+        },
+        TestCase {
+            base: 1234,
+            exp: 45,
+            result: mod_exp(1234, 45, 97) + 97,
+            expected: false,
+            desc: String::from("Unreduced result, should fail"),
+        },
+        TestCase {
+            base: 5,
+            exp: 3,
+            result: mod_exp(5, 4, 97),
+            expected: false,
+            desc: String::from("Wrong result, should fail"),
+        },
+    ];
+
+    for case in test_cases.into_iter() {
+        println!("{}", case.desc);
+
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), case.base, case.exp, case.result).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn percentage_test() -> Result<(), Error> {
+    // The circuit closure constraints the value to be a percentage of the given scale.
+    let circuit =
+        |composer: &mut StandardComposer, value: u64, scale: u64| -> Result<(), GadgetError> {
+            let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+            percentage_gadget(composer, value, scale)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // (value, scale, expected)
+    let test_cases: Vec<(u64, u64, bool)> = vec![
+        (0, DEFAULT_PERCENTAGE_SCALE, true),
+        (4_250, DEFAULT_PERCENTAGE_SCALE, true),
+        (DEFAULT_PERCENTAGE_SCALE, DEFAULT_PERCENTAGE_SCALE, true),
+        (
+            DEFAULT_PERCENTAGE_SCALE + 1,
+            DEFAULT_PERCENTAGE_SCALE,
+            false,
+        ),
+        (65_536, DEFAULT_PERCENTAGE_SCALE, false),
+        // A scale which is a power of two minus one uses all of its bits
+        (127, 127, true),
+        (128, 127, false),
+        // Integer percentages
+        (100, 100, true),
+        (101, 100, false),
+    ];
+
+    for (value, scale, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::default();
+        assert!(circuit(prover.mut_cs(), value, scale).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::default();
+        assert!(circuit(verifier.mut_cs(), 0, scale).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // A negative value (i.e. one wrapping around the field modulus) should fail
+    let mut prover = Prover::default();
+    let value = AllocatedScalar::allocate(prover.mut_cs(), -BlsScalar::one());
+    assert!(percentage_gadget(prover.mut_cs(), value, DEFAULT_PERCENTAGE_SCALE).is_ok());
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::default();
+    assert!(circuit(verifier.mut_cs(), 0, DEFAULT_PERCENTAGE_SCALE).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier
+        .verify(&proof, &vk, &vec![BlsScalar::zero()])
+        .is_err());
+
+    Ok(())
+}
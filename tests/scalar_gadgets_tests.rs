@@ -234,3 +234,601 @@ fn test_is_not_zero() -> Result<(), Error> {
     // value as a result.
     verifier.verify(&proof, &vk, &pi)
 }
+
+#[test]
+fn test_sqrt_gadget() -> Result<(), Error> {
+    // The circuit closure runs the sqrt gadget and returns both roots.
+    let circuit = |composer: &mut StandardComposer,
+                   value: BlsScalar|
+     -> Result<(AllocatedScalar, AllocatedScalar), GadgetError> {
+        let value = AllocatedScalar::allocate(composer, value);
+        sqrt_gadget(composer, value)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // A non-square value should err
+    let mut prover = Prover::new(b"testing");
+    let non_square = (1..)
+        .map(BlsScalar::from)
+        .find(|x| x.sqrt().is_none().unwrap_u8() == 1u8)
+        .unwrap();
+    assert!(circuit(prover.mut_cs(), non_square).is_err());
+
+    for value in [0u64, 1, 4, 49, 12_345_678].iter() {
+        let value = BlsScalar::from(*value).square();
+
+        // Proving
+        let mut prover = Prover::new(b"testing");
+        let (root, neg_root) = circuit(prover.mut_cs(), value).expect("Square root exists");
+        // Both roots square to the value and add up to zero
+        assert_eq!(root.scalar * root.scalar, value);
+        assert_eq!(neg_root.scalar * neg_root.scalar, value);
+        assert_eq!(root.scalar + neg_root.scalar, BlsScalar::zero());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_ok());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_reciprocal_gadget() -> Result<(), Error> {
+    // The circuit closure runs the reciprocal gadget and returns the reciprocal.
+    let circuit =
+        |composer: &mut StandardComposer, x: BlsScalar| -> Result<AllocatedScalar, GadgetError> {
+            let x = AllocatedScalar::allocate(composer, x);
+            reciprocal_gadget(composer, x)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // Zero doesn't have a reciprocal and should err
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), BlsScalar::zero()).is_err());
+
+    for x in [
+        BlsScalar::one(),
+        BlsScalar::from(3),
+        -BlsScalar::from(7),
+        BlsScalar::random(&mut rand::thread_rng()),
+    ]
+    .iter()
+    {
+        // Proving
+        let mut prover = Prover::new(b"testing");
+        let recip = circuit(prover.mut_cs(), *x).expect("Reciprocal exists");
+        assert_eq!(recip.scalar * x, BlsScalar::one());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), BlsScalar::one()).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_ok());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_is_successor_gadget() -> Result<(), Error> {
+    // The circuit closure runs the is_successor gadget, which constraints `next` to be `prev + 1`.
+    let circuit = |composer: &mut StandardComposer,
+                   prev: BlsScalar,
+                   next: BlsScalar|
+     -> Result<(), GadgetError> {
+        let prev = AllocatedScalar::allocate(composer, prev);
+        let next = AllocatedScalar::allocate(composer, next);
+        is_successor_gadget(composer, prev, next)?;
+
+        // A single gate is too small a circuit to preprocess, so also check a
+        // fixed successor pair built from constants.
+        let zero = AllocatedScalar {
+            var: composer.add_witness_to_circuit_description(BlsScalar::zero()),
+            scalar: BlsScalar::zero(),
+        };
+        let one = AllocatedScalar {
+            var: composer.add_witness_to_circuit_description(BlsScalar::one()),
+            scalar: BlsScalar::one(),
+        };
+        is_successor_gadget(composer, zero, one)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let rand = BlsScalar::random(&mut rand::thread_rng());
+    let test_cases = vec![
+        (BlsScalar::zero(), BlsScalar::one(), true),
+        (rand, rand + BlsScalar::one(), true),
+        (-BlsScalar::one(), BlsScalar::zero(), true),
+        // A gap between both values should fail
+        (BlsScalar::from(5), BlsScalar::from(7), false),
+        // Equal values should fail
+        (BlsScalar::from(5), BlsScalar::from(5), false),
+    ];
+
+    for (prev, next, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), prev, next).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), BlsScalar::zero(), BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_coprime_gadget() -> Result<(), Error> {
+    // The circuit closure runs the coprime gadget with the provided Bézout coefficients.
+    let signed = |x: i64| {
+        if x < 0 {
+            -BlsScalar::from(x.unsigned_abs())
+        } else {
+            BlsScalar::from(x as u64)
+        }
+    };
+    let circuit = |composer: &mut StandardComposer,
+                   a: u64,
+                   b: u64,
+                   s: BlsScalar,
+                   t: BlsScalar|
+     -> Result<(), GadgetError> {
+        let a = AllocatedScalar::allocate(composer, BlsScalar::from(a));
+        let b = AllocatedScalar::allocate(composer, BlsScalar::from(b));
+        let s = AllocatedScalar::allocate(composer, s);
+        let t = AllocatedScalar::allocate(composer, t);
+        coprime_gadget(composer, a, b, s, t, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    // (a, b, s, t, expected)
+    let test_cases: Vec<(u64, u64, BlsScalar, BlsScalar, bool)> = vec![
+        (3, 7, signed(5), signed(-2), true),
+        (35, 64, signed(11), signed(-6), true),
+        (1, 1_000, signed(1), signed(0), true),
+        // 4 and 6 aren't coprime, so no integer coefficients satisfy the identity
+        (4, 6, signed(1), signed(0), false),
+        (4, 6, signed(2), signed(-1), false),
+        (4, 6, signed(-1), signed(1), false),
+        (4, 6, signed(-4), signed(3), false),
+        // s = 1/4, t = 0 satisfies the identity in the field, but 1/4 isn't a small integer
+        (4, 6, BlsScalar::from(4).invert().unwrap(), signed(0), false),
+    ];
+
+    for (a, b, s, t, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), a, b, s, t).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(
+            verifier.mut_cs(),
+            0,
+            0,
+            BlsScalar::zero(),
+            BlsScalar::zero()
+        )
+        .is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // Widths whose integer products could wrap around the field modulus should err
+    let mut prover = Prover::default();
+    let zero = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::zero());
+    assert!(coprime_gadget(prover.mut_cs(), zero, zero, zero, zero, 127).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_interpolation_consistency_gadget() -> Result<(), Error> {
+    // p(x) = 5 + 3x + 2x^2 + x^3
+    let coeffs = [5u64, 3, 2, 1];
+    let eval = |x: u64| coeffs.iter().rev().fold(0, |acc, c| acc * x + c);
+
+    // The circuit closure runs the interpolation consistency gadget, which constraints
+    // the private polynomial to pass through the private points.
+    let circuit = |composer: &mut StandardComposer,
+                   coeffs: &[u64],
+                   points: &[(u64, u64)]|
+     -> Result<(), GadgetError> {
+        let coeffs: Vec<AllocatedScalar> = coeffs
+            .iter()
+            .map(|c| AllocatedScalar::allocate(composer, BlsScalar::from(*c)))
+            .collect();
+        let points: Vec<(AllocatedScalar, AllocatedScalar)> = points
+            .iter()
+            .map(|(x, y)| {
+                (
+                    AllocatedScalar::allocate(composer, BlsScalar::from(*x)),
+                    AllocatedScalar::allocate(composer, BlsScalar::from(*y)),
+                )
+            })
+            .collect();
+        interpolation_consistency_gadget(composer, &coeffs, &points)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // The evaluation itself matches the expected value
+    let mut prover = Prover::new(b"testing");
+    let allocated: Vec<AllocatedScalar> = coeffs
+        .iter()
+        .map(|c| AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::from(*c)))
+        .collect();
+    let x = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::from(7));
+    let evaluation = poly_eval_gadget(prover.mut_cs(), &allocated, x);
+    assert_eq!(evaluation.scalar, BlsScalar::from(eval(7)));
+
+    struct TestCase {
+        points: Vec<(u64, u64)>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            points: vec![(0, eval(0)), (1, eval(1)), (10, eval(10))],
+            expected: true,
+            desc: String::from("Points on the polynomial, should pass"),
+        },
+        TestCase {
+            points: vec![(0, eval(0)), (1, eval(1) + 1), (10, eval(10))],
+            expected: false,
+            desc: String::from("Point off the polynomial, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &coeffs, &case.points).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 4], &[(0, 0); 3]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_on_line_gadget() -> Result<(), Error> {
+    // The circuit closure runs the on_line gadget, which constraints the point (x, y)
+    // to lie on the line y = m * x + b.
+    let circuit = |composer: &mut StandardComposer,
+                   x: BlsScalar,
+                   y: BlsScalar,
+                   m: BlsScalar,
+                   b: BlsScalar|
+     -> Result<(), GadgetError> {
+        let x = AllocatedScalar::allocate(composer, x);
+        let y = AllocatedScalar::allocate(composer, y);
+        let m = AllocatedScalar::allocate(composer, m);
+        let b = AllocatedScalar::allocate(composer, b);
+        on_line_gadget(composer, x, y, m, b)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let m = BlsScalar::random(&mut rand::thread_rng());
+    let b = BlsScalar::random(&mut rand::thread_rng());
+    let x = BlsScalar::random(&mut rand::thread_rng());
+    let test_cases = vec![
+        (
+            BlsScalar::from(3),
+            BlsScalar::from(17),
+            BlsScalar::from(4),
+            BlsScalar::from(5),
+            true,
+        ),
+        (BlsScalar::zero(), b, m, b, true),
+        (x, m * x + b, m, b, true),
+        // Points off the line should fail
+        (x, m * x + b + BlsScalar::one(), m, b, false),
+        (
+            BlsScalar::from(3),
+            BlsScalar::from(17),
+            BlsScalar::from(5),
+            BlsScalar::from(4),
+            false,
+        ),
+    ];
+
+    for (x, y, m, b, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), x, y, m, b).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::new(b"testing");
+        let zero = BlsScalar::zero();
+        assert!(circuit(verifier.mut_cs(), zero, zero, zero, zero).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_shamir_share_valid_gadget() -> Result<(), Error> {
+    // The circuit closure runs the shamir_share_valid gadget, which constraints the share
+    // to be the evaluation at x of the polynomial whose constant term is the secret.
+    let circuit = |composer: &mut StandardComposer,
+                   coeffs: &[BlsScalar],
+                   x: BlsScalar,
+                   share: BlsScalar|
+     -> Result<(), GadgetError> {
+        let coeffs: Vec<AllocatedScalar> = coeffs
+            .iter()
+            .map(|c| AllocatedScalar::allocate(composer, *c))
+            .collect();
+        let x = AllocatedScalar::allocate(composer, x);
+        let share = AllocatedScalar::allocate(composer, share);
+        shamir_share_valid_gadget(composer, &coeffs, x, share)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // Polynomial of degree 2 (threshold 3) hiding a random secret
+    let coeffs: Vec<BlsScalar> = (0..3)
+        .map(|_| BlsScalar::random(&mut rand::thread_rng()))
+        .collect();
+    let share_at = |x: u64| {
+        let x = BlsScalar::from(x);
+        coeffs[0] + coeffs[1] * x + coeffs[2] * x * x
+    };
+
+    let test_cases = vec![
+        (1, share_at(1), true),
+        (2, share_at(2), true),
+        (5, share_at(5), true),
+        // A share of another point should fail
+        (3, share_at(4), false),
+        // A modified share should fail
+        (5, share_at(5) + BlsScalar::one(), false),
+    ];
+
+    for (x, share, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &coeffs, BlsScalar::from(x), share).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::new(b"testing");
+        let zero = BlsScalar::zero();
+        assert!(circuit(verifier.mut_cs(), &[zero; 3], zero, zero).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_ntt_consistency_gadget() -> Result<(), Error> {
+    // Powers of the 4th root of unity i = sqrt(-1)
+    let i = (-BlsScalar::one()).sqrt().unwrap();
+    let roots = vec![BlsScalar::one(), i, -BlsScalar::one(), -i];
+
+    // The circuit closure runs the ntt_consistency gadget, which constraints the output
+    // to be the transform of the input over the roots.
+    let circuit = |composer: &mut StandardComposer,
+                   input: &[BlsScalar],
+                   output: &[BlsScalar]|
+     -> Result<(), GadgetError> {
+        let input: Vec<AllocatedScalar> = input
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        let output: Vec<AllocatedScalar> = output
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        ntt_consistency_gadget(composer, &input, &output, &roots)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let input: Vec<BlsScalar> = vec![1u64, 2, 3, 4]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+    let two = BlsScalar::from(2);
+    // NTT([1, 2, 3, 4]) = [10, -2 - 2i, -2, -2 + 2i]
+    let transform = vec![BlsScalar::from(10), -two - two * i, -two, -two + two * i];
+    let mut wrong_transform = transform.clone();
+    wrong_transform.swap(1, 3);
+
+    let test_cases = vec![(transform, true), (wrong_transform, false)];
+
+    for (output, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &input, &output).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::new(b"testing");
+        let zeros = [BlsScalar::zero(); 4];
+        assert!(circuit(verifier.mut_cs(), &zeros, &zeros).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // There must be one output per root
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &input, &[BlsScalar::zero(); 3]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_has_root_gadget() -> Result<(), Error> {
+    // p(x) = 10 - 7x + x^2 = (x - 2) * (x - 5)
+    let coeffs = [BlsScalar::from(10), -BlsScalar::from(7), BlsScalar::one()];
+
+    // The circuit closure runs the has_root gadget, which constraints the private
+    // value to be a root of the private polynomial.
+    let circuit = |composer: &mut StandardComposer,
+                   coeffs: &[BlsScalar],
+                   root: u64|
+     -> Result<(), GadgetError> {
+        let coeffs: Vec<AllocatedScalar> = coeffs
+            .iter()
+            .map(|c| AllocatedScalar::allocate(composer, *c))
+            .collect();
+        let root = AllocatedScalar::allocate(composer, BlsScalar::from(root));
+        has_root_gadget(composer, &coeffs, root)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    struct TestCase {
+        root: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            root: 2,
+            expected: true,
+            desc: String::from("First root, should pass"),
+        },
+        TestCase {
+            root: 5,
+            expected: true,
+            desc: String::from("Second root, should pass"),
+        },
+        TestCase {
+            root: 3,
+            expected: false,
+            desc: String::from("Non-root, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &coeffs, case.root).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[BlsScalar::zero(); 3], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
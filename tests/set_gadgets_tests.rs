@@ -9,7 +9,7 @@ extern crate plonk_gadgets;
 
 use dusk_plonk::prelude::*;
 use plonk_gadgets::AllocatedScalar;
-use plonk_gadgets::{Error as GadgetError, SetGadgets::*};
+use plonk_gadgets::{Error as GadgetError, RangeGadgets, SetGadgets::*};
 
 #[test]
 fn test_vector_non_membership_gadget() -> Result<(), Error> {
@@ -363,3 +363,3052 @@ fn test_set_uniqueness_gadget() -> Result<(), Error> {
 
     Ok(())
 }
+
+#[test]
+fn test_vectors_equal_gadget() -> Result<(), Error> {
+    // The circuit closure runs the vectors_equal gadget, which constraints both vectors
+    // to be equal element-wise.
+    let circuit = |composer: &mut StandardComposer,
+                   a: &Vec<BlsScalar>,
+                   b: &Vec<BlsScalar>|
+     -> Result<(), GadgetError> {
+        let a: Vec<AllocatedScalar> = a
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        let b: Vec<AllocatedScalar> = b
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        vectors_equal_gadget(composer, &a, &b)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        a: Vec<BlsScalar>,
+        b: Vec<BlsScalar>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            a: vec![BlsScalar::from(3), BlsScalar::from(4), BlsScalar::from(5)],
+            b: vec![BlsScalar::from(3), BlsScalar::from(4), BlsScalar::from(5)],
+            desc: String::from("Equal vectors, should pass"),
+            expected: true,
+        },
+        TestCase {
+            a: vec![BlsScalar::from(3), BlsScalar::from(4), BlsScalar::from(5)],
+            b: vec![BlsScalar::from(3), BlsScalar::from(7), BlsScalar::from(5)],
+            desc: String::from("One differing element, should fail"),
+            expected: false,
+        },
+        TestCase {
+            a: vec![BlsScalar::from(3), BlsScalar::from(4), BlsScalar::from(5)],
+            b: vec![BlsScalar::from(5), BlsScalar::from(4), BlsScalar::from(3)],
+            desc: String::from("Same elements in a different order, should fail"),
+            expected: false,
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.a, &case.b).is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec().clone();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![BlsScalar::zero(); case.a.len()];
+        assert!(circuit(verifier.mut_cs(), &placeholder, &placeholder).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    // Vectors of different length can't be compared element-wise
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(
+        prover.mut_cs(),
+        &vec![BlsScalar::from(3), BlsScalar::from(4)],
+        &vec![BlsScalar::from(3)],
+    )
+    .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_prefix_sum_gadget() -> Result<(), Error> {
+    // The circuit closure runs the prefix_sum gadget and constraints each of the
+    // returned partial sums to be equal to the expected ones, which are set as Public Inputs.
+    let circuit = |composer: &mut StandardComposer,
+                   vector: &Vec<BlsScalar>,
+                   expected: &Vec<BlsScalar>|
+     -> Vec<BlsScalar> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        let prefix_sums = prefix_sum_gadget(composer, &vector);
+        for (sum, expected) in prefix_sums.iter().zip(expected.iter()) {
+            composer.constrain_to_constant(sum.var, BlsScalar::zero(), Some(-expected));
+        }
+        prefix_sums.iter().map(|sum| sum.scalar).collect()
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        vector: Vec<u64>,
+        expected: Vec<u64>,
+        valid: bool,
+    }
+
+    let test_cases = vec![
+        TestCase {
+            vector: vec![1, 2, 3, 4],
+            expected: vec![1, 3, 6, 10],
+            valid: true,
+        },
+        TestCase {
+            vector: vec![5, 0, 0, 7, 1],
+            expected: vec![5, 5, 5, 12, 13],
+            valid: true,
+        },
+        TestCase {
+            vector: vec![9],
+            expected: vec![9],
+            valid: true,
+        },
+        TestCase {
+            vector: vec![1, 2, 3, 4],
+            expected: vec![1, 3, 7, 10],
+            valid: false,
+        },
+    ];
+
+    for case in test_cases.iter() {
+        let vector: Vec<BlsScalar> = case.vector.iter().map(|x| BlsScalar::from(*x)).collect();
+        let expected: Vec<BlsScalar> = case.expected.iter().map(|x| BlsScalar::from(*x)).collect();
+
+        let mut prover = Prover::new(b"testing");
+        let prefix_sums = circuit(prover.mut_cs(), &vector, &expected);
+        // Natively computed prefix sums should match the in-circuit ones
+        let mut native_sum = BlsScalar::zero();
+        for (elem, sum) in vector.iter().zip(prefix_sums.iter()) {
+            native_sum += elem;
+            assert_eq!(native_sum, *sum);
+        }
+        let pi = prover.mut_cs().construct_dense_pi_vec().clone();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![BlsScalar::zero(); vector.len()];
+        circuit(verifier.mut_cs(), &placeholder, &placeholder);
+        verifier.preprocess(&ck)?;
+        if case.valid {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bloom_membership_gadget() -> Result<(), Error> {
+    // The i-th hash function of the filter is h_i(x) = ((2i + 3) * x + i) mod 16,
+    // computed both natively and in the circuit.
+    let hash = |x: u64, i: usize| ((2 * i as u64 + 3) * x + i as u64) % 16;
+    let hash_fn = |composer: &mut StandardComposer,
+                   x: AllocatedScalar,
+                   i: usize|
+     -> Result<AllocatedScalar, GadgetError> {
+        let zero = composer.zero_var();
+        let linear = AllocatedScalar {
+            var: composer.add(
+                (BlsScalar::from(2 * i as u64 + 3), x.var),
+                (BlsScalar::zero(), zero),
+                BlsScalar::from(i as u64),
+                None,
+            ),
+            scalar: BlsScalar::from(2 * i as u64 + 3) * x.scalar + BlsScalar::from(i as u64),
+        };
+        RangeGadgets::mod_reduce_gadget(composer, linear, 16, 16)
+    };
+    let hash_indices = |x: u64| -> Vec<usize> { (0..3).map(|i| hash(x, i) as usize).collect() };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // Filter with the positions of two elements set: 5 and 10
+    let mut filter = vec![false; 16];
+    for index in hash_indices(5).iter().chain(hash_indices(10).iter()) {
+        filter[*index] = true;
+    }
+
+    struct TestCase {
+        value: u64,
+        hash_indices: Vec<usize>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            value: 5,
+            hash_indices: hash_indices(5),
+            expected: true,
+            desc: String::from("Element present in the filter, should pass"),
+        },
+        TestCase {
+            value: 10,
+            hash_indices: hash_indices(10),
+            expected: true,
+            desc: String::from("Another element present in the filter, should pass"),
+        },
+        TestCase {
+            value: 2,
+            hash_indices: hash_indices(2),
+            expected: false,
+            desc: String::from("Element with an unset bit in the filter, should fail"),
+        },
+        TestCase {
+            value: 2,
+            hash_indices: hash_indices(5),
+            expected: false,
+            desc: String::from("Positions of another element, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        let value = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::from(case.value));
+        assert!(bloom_membership_gadget(
+            prover.mut_cs(),
+            value,
+            &case.hash_indices,
+            &filter,
+            hash_fn
+        )
+        .is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let value = AllocatedScalar::allocate(verifier.mut_cs(), BlsScalar::zero());
+        assert!(bloom_membership_gadget(
+            verifier.mut_cs(),
+            value,
+            &case.hash_indices,
+            &filter,
+            hash_fn
+        )
+        .is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // Positions outside of the filter should err
+    let mut prover = Prover::new(b"testing");
+    let value = AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::from(5));
+    assert!(bloom_membership_gadget(prover.mut_cs(), value, &[1, 16], &filter, hash_fn).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_set_cardinality_gadget() -> Result<(), Error> {
+    // The circuit closure runs the set_cardinality gadget, which constraints the set to
+    // contain exactly `n` distinct non-zero elements.
+    let circuit = |composer: &mut StandardComposer,
+                   set: &Vec<BlsScalar>,
+                   n: u64|
+     -> Result<(), GadgetError> {
+        let assigned_set: Vec<AllocatedScalar> = set
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        set_cardinality_gadget(composer, &assigned_set, n)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        set: Vec<BlsScalar>,
+        n: u64,
+        expected_witness: bool,
+        expected_prover: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            set: vec![3, 4, 5, 6]
+                .iter()
+                .map(|x| BlsScalar::from(*x))
+                .collect(),
+            n: 4,
+            desc: String::from("Full set of distinct elements"),
+            expected_witness: true,
+            expected_prover: true,
+        },
+        TestCase {
+            set: vec![3, 0, 5, 0]
+                .iter()
+                .map(|x| BlsScalar::from(*x))
+                .collect(),
+            n: 2,
+            desc: String::from("Zero-padded set of distinct elements"),
+            expected_witness: true,
+            expected_prover: true,
+        },
+        TestCase {
+            set: vec![0, 0, 0, 0]
+                .iter()
+                .map(|x| BlsScalar::from(*x))
+                .collect(),
+            n: 0,
+            desc: String::from("Empty set, all padding"),
+            expected_witness: true,
+            expected_prover: true,
+        },
+        TestCase {
+            set: vec![3, 0, 5, 0]
+                .iter()
+                .map(|x| BlsScalar::from(*x))
+                .collect(),
+            n: 4,
+            desc: String::from("Padding claimed as part of the set, should fail"),
+            expected_witness: false,
+            expected_prover: true,
+        },
+        TestCase {
+            set: vec![3, 4, 3, 0]
+                .iter()
+                .map(|x| BlsScalar::from(*x))
+                .collect(),
+            n: 3,
+            desc: String::from("Repeated element, prover shouldn't construct a valid proof"),
+            expected_witness: false,
+            expected_prover: false,
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        if case.expected_prover {
+            assert!(circuit(prover.mut_cs(), &case.set, case.n).is_ok());
+        } else {
+            assert!(circuit(prover.mut_cs(), &case.set, case.n).is_err());
+            continue;
+        }
+        let pi = prover.mut_cs().construct_dense_pi_vec().clone();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![BlsScalar::zero(); case.set.len()];
+        assert!(circuit(verifier.mut_cs(), &placeholder, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected_witness {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_balance_gadget() -> Result<(), Error> {
+    // The circuit closure runs the balance gadget, which constraints the inputs to be equal
+    // to the outputs plus the fee.
+    let circuit = |composer: &mut StandardComposer,
+                   inputs: &[u64],
+                   outputs: &[u64],
+                   fee: u64|
+     -> Result<(), GadgetError> {
+        let inputs: Vec<AllocatedScalar> = inputs
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let outputs: Vec<AllocatedScalar> = outputs
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let fee = AllocatedScalar::allocate(composer, BlsScalar::from(fee));
+        balance_gadget(composer, &inputs, &outputs, fee)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        inputs: Vec<u64>,
+        outputs: Vec<u64>,
+        fee: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            inputs: vec![100, 50],
+            outputs: vec![120, 30],
+            fee: 0,
+            expected: true,
+            desc: String::from("Balanced transaction without fee"),
+        },
+        TestCase {
+            inputs: vec![100, 50],
+            outputs: vec![120, 25],
+            fee: 5,
+            expected: true,
+            desc: String::from("Balanced transaction with fee"),
+        },
+        TestCase {
+            inputs: vec![100, 50],
+            outputs: vec![120, 30],
+            fee: 5,
+            expected: false,
+            desc: String::from("Fee not covered by the inputs, should fail"),
+        },
+        TestCase {
+            inputs: vec![100, 50],
+            outputs: vec![100, 30],
+            fee: 5,
+            expected: false,
+            desc: String::from("Inputs exceeding outputs plus fee, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.inputs, &case.outputs, case.fee).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0, 0], &[0, 0], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_masked_equal_gadget() -> Result<(), Error> {
+    // The circuit closure runs the masked equal gadget, which constraints the witness vector
+    // to match the public vector wherever the mask is set.
+    let circuit = |composer: &mut StandardComposer,
+                   a: &[u64],
+                   b: &[BlsScalar],
+                   mask: &[u64]|
+     -> Result<(), GadgetError> {
+        let a: Vec<AllocatedScalar> = a
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let mask: Vec<AllocatedScalar> = mask
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        masked_equal_gadget(composer, &a, b, &mask)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        a: Vec<u64>,
+        mask: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let b: Vec<BlsScalar> = vec![1u64, 2, 3, 4]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            a: vec![1, 2, 3, 4],
+            mask: vec![1, 1, 1, 1],
+            expected: true,
+            desc: String::from("Full mask over equal vectors"),
+        },
+        TestCase {
+            a: vec![1, 7, 3, 9],
+            mask: vec![1, 0, 1, 0],
+            expected: true,
+            desc: String::from("Differences only at unmasked positions"),
+        },
+        TestCase {
+            a: vec![5, 6, 7, 8],
+            mask: vec![0, 0, 0, 0],
+            expected: true,
+            desc: String::from("Empty mask leaves the vector unconstrained"),
+        },
+        TestCase {
+            a: vec![1, 7, 3, 4],
+            mask: vec![1, 1, 0, 0],
+            expected: false,
+            desc: String::from("Difference at a masked position, should fail"),
+        },
+        TestCase {
+            a: vec![1, 2, 3, 4],
+            mask: vec![2, 0, 0, 0],
+            expected: false,
+            desc: String::from("Non-boolean mask, should fail"),
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[1, 2, 3], &b, &[1, 1, 1]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.a, &b, &case.mask).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0, 0, 0, 0], &b, &[0, 0, 0, 0]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_set_difference_gadget() -> Result<(), Error> {
+    // The circuit closure runs the set difference gadget, which constraints the result
+    // to contain exactly the elements of the universe which weren't removed.
+    let circuit = |composer: &mut StandardComposer,
+                   universe: &[BlsScalar],
+                   removed: &[BlsScalar],
+                   result: &[BlsScalar]|
+     -> Result<(), GadgetError> {
+        let removed: Vec<AllocatedScalar> = removed
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        let result: Vec<AllocatedScalar> = result
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, *x))
+            .collect();
+        set_difference_gadget(composer, universe, &removed, &result)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    struct TestCase {
+        removed: Vec<BlsScalar>,
+        result: Vec<BlsScalar>,
+        expected_witness: bool,
+        expected_prover: bool,
+        desc: String,
+    }
+
+    let universe: Vec<BlsScalar> = vec![3u64, 5, 7, 9]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            removed: vec![BlsScalar::from(5)],
+            result: vec![BlsScalar::from(9), BlsScalar::from(3), BlsScalar::from(7)],
+            expected_witness: true,
+            expected_prover: true,
+            desc: String::from("Removing a single element"),
+        },
+        TestCase {
+            removed: vec![BlsScalar::from(3), BlsScalar::from(9)],
+            result: vec![BlsScalar::from(5), BlsScalar::from(7)],
+            expected_witness: true,
+            expected_prover: true,
+            desc: String::from("Removing two elements"),
+        },
+        TestCase {
+            removed: vec![BlsScalar::from(3), BlsScalar::from(9)],
+            result: vec![BlsScalar::from(5), BlsScalar::from(11)],
+            expected_witness: true,
+            expected_prover: false,
+            desc: String::from("Result element not in the universe, should fail"),
+        },
+        TestCase {
+            removed: vec![BlsScalar::from(3), BlsScalar::from(9)],
+            result: vec![BlsScalar::from(5), BlsScalar::from(9)],
+            expected_witness: false,
+            expected_prover: false,
+            desc: String::from("Result containing a removed element, should fail"),
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &universe, &universe[..1], &universe[..2]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        let res = circuit(prover.mut_cs(), &universe, &case.removed, &case.result);
+        assert_eq!(res.is_ok(), case.expected_witness);
+        if !case.expected_witness {
+            continue;
+        }
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = &universe[..case.removed.len()];
+        let placeholder_result = &universe[case.removed.len()..];
+        assert!(circuit(
+            verifier.mut_cs(),
+            &universe,
+            placeholder,
+            placeholder_result
+        )
+        .is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected_prover {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_membership_in_any_gadget() -> Result<(), Error> {
+    // The circuit closure runs the membership in any gadget, which constraints the witness to
+    // be part of at least one of the sets.
+    let circuit = |composer: &mut StandardComposer,
+                   sets: &[Vec<BlsScalar>],
+                   witness: BlsScalar|
+     -> Result<(), GadgetError> {
+        let value = AllocatedScalar::allocate(composer, witness);
+        membership_in_any_gadget(composer, value, sets)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        witness: BlsScalar,
+        expected: bool,
+        desc: String,
+    }
+
+    let sets: Vec<Vec<BlsScalar>> = vec![
+        vec![BlsScalar::from(3), BlsScalar::from(5), BlsScalar::from(7)],
+        vec![BlsScalar::from(7), BlsScalar::from(11)],
+    ];
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            witness: BlsScalar::from(5),
+            expected: true,
+            desc: String::from("Value in the first set"),
+        },
+        TestCase {
+            witness: BlsScalar::from(11),
+            expected: true,
+            desc: String::from("Value in the second set"),
+        },
+        TestCase {
+            witness: BlsScalar::from(7),
+            expected: true,
+            desc: String::from("Value in both sets"),
+        },
+        TestCase {
+            witness: BlsScalar::from(9),
+            expected: false,
+            desc: String::from("Value in none of the sets, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &sets, case.witness).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &sets, BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_non_membership_in_all_gadget() -> Result<(), Error> {
+    // The circuit closure runs the non membership in all gadget, which constraints the witness
+    // to not be part of any of the sets.
+    let circuit = |composer: &mut StandardComposer,
+                   sets: &[Vec<BlsScalar>],
+                   witness: BlsScalar|
+     -> Result<(), GadgetError> {
+        let value = AllocatedScalar::allocate(composer, witness);
+        non_membership_in_all_gadget(composer, value, sets)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        witness: BlsScalar,
+        expected_witness: bool,
+        desc: String,
+    }
+
+    let sets: Vec<Vec<BlsScalar>> = vec![
+        vec![BlsScalar::from(3), BlsScalar::from(5)],
+        vec![BlsScalar::from(7), BlsScalar::from(11)],
+    ];
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            witness: BlsScalar::from(9),
+            expected_witness: true,
+            desc: String::from("Value absent from all sets"),
+        },
+        TestCase {
+            witness: BlsScalar::from(3),
+            expected_witness: false,
+            desc: String::from("Value in the first set, should fail"),
+        },
+        TestCase {
+            witness: BlsScalar::from(11),
+            expected_witness: false,
+            desc: String::from("Value in the second set, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        let res = circuit(prover.mut_cs(), &sets, case.witness);
+        assert_eq!(res.is_ok(), case.expected_witness);
+        if !case.expected_witness {
+            continue;
+        }
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &sets, BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_ok());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_set_membership_with_index_gadget() -> Result<(), Error> {
+    // The circuit closure runs the set_membership_with_index gadget, which constraints the value
+    // to be part of the set and returns the one-hot bit-map of its position.
+    let circuit = |composer: &mut StandardComposer,
+                   set: &Vec<BlsScalar>,
+                   value: BlsScalar|
+     -> Result<Vec<AllocatedScalar>, GadgetError> {
+        let assigned_value = AllocatedScalar::allocate(composer, value);
+        set_membership_with_index_gadget(composer, set, assigned_value)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let set: Vec<BlsScalar> = vec![3u64, 5, 7, 9]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    for (index, value) in set.iter().enumerate() {
+        let mut prover = Prover::new(b"testing");
+        let bits = circuit(prover.mut_cs(), &set, *value).expect("Value is part of the set");
+        // The bit-map is one-hot at the position of the value
+        for (i, bit) in bits.iter().enumerate() {
+            let expected_bit = if i == index {
+                BlsScalar::one()
+            } else {
+                BlsScalar::zero()
+            };
+            assert_eq!(bit.scalar, expected_bit);
+        }
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &set, BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_index_to_onehot_gadget() -> Result<(), Error> {
+    // The circuit closure runs the index_to_onehot gadget over vectors of 5 elements and
+    // returns the one-hot encoding of the index.
+    let circuit = |composer: &mut StandardComposer,
+                   index: BlsScalar|
+     -> Result<Vec<AllocatedScalar>, GadgetError> {
+        let index = AllocatedScalar::allocate(composer, index);
+        index_to_onehot_gadget(composer, index, 5, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    for index in 0..5 {
+        let mut prover = Prover::new(b"testing");
+        let bits = circuit(prover.mut_cs(), BlsScalar::from(index as u64)).expect("Valid index");
+        // The encoding is one-hot at the position of the index
+        for (i, bit) in bits.iter().enumerate() {
+            let expected_bit = if i == index {
+                BlsScalar::one()
+            } else {
+                BlsScalar::zero()
+            };
+            assert_eq!(bit.scalar, expected_bit);
+        }
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier
+            .verify(&proof, &vk, &vec![BlsScalar::zero()])
+            .is_ok());
+    }
+
+    // An index out of the vector bounds can't be encoded
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), BlsScalar::from(5)).is_ok());
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::new(b"testing");
+    assert!(circuit(verifier.mut_cs(), BlsScalar::zero()).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier
+        .verify(&proof, &vk, &vec![BlsScalar::zero()])
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_sorted_commitment_gadget() -> Result<(), Error> {
+    // The circuit closure runs the sorted commitment gadget, which constraints the second
+    // vector to be the sorted version of the first one.
+    let circuit = |composer: &mut StandardComposer,
+                   input: &[u64],
+                   sorted: &[u64]|
+     -> Result<(), GadgetError> {
+        let input: Vec<AllocatedScalar> = input
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let sorted: Vec<AllocatedScalar> = sorted
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        sorted_commitment_gadget(composer, &input, &sorted, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        input: Vec<u64>,
+        sorted: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            input: vec![42, 7, 1000, 7],
+            sorted: vec![7, 7, 42, 1000],
+            expected: true,
+            desc: String::from("Correct sorted version"),
+        },
+        TestCase {
+            input: vec![42, 7, 1000, 7],
+            sorted: vec![7, 42, 7, 1000],
+            expected: false,
+            desc: String::from("Permutation which isn't sorted, should fail"),
+        },
+        TestCase {
+            input: vec![42, 7, 1000, 7],
+            sorted: vec![7, 8, 42, 1000],
+            expected: false,
+            desc: String::from("Sorted vector which isn't a permutation, should fail"),
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[1, 2, 3], &[1, 2]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.input, &case.sorted).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 4], &[0; 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_all_equal_constant_gadget() -> Result<(), Error> {
+    // The circuit closure runs the all equal constant gadget, which constraints every element
+    // of the vector to be equal to 7.
+    let circuit = |composer: &mut StandardComposer, vector: &[u64]| -> Result<(), GadgetError> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        all_equal_constant_gadget(composer, &vector, BlsScalar::from(7))
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // An empty vector adds no constraints, leaving nothing to prove, so only check that
+    // the gadget accepts it.
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[]).is_ok());
+
+    struct TestCase {
+        vector: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            vector: vec![7, 7, 7],
+            expected: true,
+            desc: String::from("All elements equal to the constant"),
+        },
+        TestCase {
+            vector: vec![7, 8, 7],
+            expected: false,
+            desc: String::from("Deviating element, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.vector).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &vec![0; case.vector.len()]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_is_rotation_gadget() -> Result<(), Error> {
+    // The circuit closure runs the is_rotation gadget, which constraints the second vector
+    // to be the first one rotated by the shift.
+    let circuit = |composer: &mut StandardComposer,
+                   a: &[u64],
+                   b: &[u64],
+                   shift: u64|
+     -> Result<(), GadgetError> {
+        let a: Vec<AllocatedScalar> = a
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let b: Vec<AllocatedScalar> = b
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let shift = AllocatedScalar::allocate(composer, BlsScalar::from(shift));
+        is_rotation_gadget(composer, &a, &b, shift)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        b: Vec<u64>,
+        shift: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let a = vec![1, 2, 3, 4];
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            b: vec![1, 2, 3, 4],
+            shift: 0,
+            expected: true,
+            desc: String::from("Identity rotation"),
+        },
+        TestCase {
+            b: vec![4, 1, 2, 3],
+            shift: 1,
+            expected: true,
+            desc: String::from("Rotation by one"),
+        },
+        TestCase {
+            b: vec![2, 3, 4, 1],
+            shift: 3,
+            expected: true,
+            desc: String::from("Rotation by three"),
+        },
+        TestCase {
+            b: vec![2, 3, 4, 1],
+            shift: 1,
+            expected: false,
+            desc: String::from("Wrong shift, should fail"),
+        },
+        TestCase {
+            b: vec![2, 1, 3, 4],
+            shift: 1,
+            expected: false,
+            desc: String::from("Not a rotation, should fail"),
+        },
+        TestCase {
+            b: vec![1, 2, 3, 4],
+            shift: 4,
+            expected: false,
+            desc: String::from("Shift out of bounds, should fail"),
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &a, &[1, 2, 3], 0).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &a, &case.b, case.shift).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 4], &[0; 4], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_set_equal_public_gadget() -> Result<(), Error> {
+    // The circuit closure runs the set equal public gadget, which constraints the private
+    // vector to contain the same elements as the public one.
+    let circuit = |composer: &mut StandardComposer,
+                   private: &[u64],
+                   public: &[BlsScalar]|
+     -> Result<(), GadgetError> {
+        let private: Vec<AllocatedScalar> = private
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        set_equal_public_gadget(composer, &private, public)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        private: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let public: Vec<BlsScalar> = vec![3u64, 5, 5, 9]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            private: vec![3, 5, 5, 9],
+            expected: true,
+            desc: String::from("Equal sets"),
+        },
+        TestCase {
+            private: vec![5, 9, 3, 5],
+            expected: true,
+            desc: String::from("Reordered private set"),
+        },
+        TestCase {
+            private: vec![5, 9, 3, 3],
+            expected: false,
+            desc: String::from("Different multiplicities, should fail"),
+        },
+        TestCase {
+            private: vec![5, 9, 3, 7],
+            expected: false,
+            desc: String::from("Differing element, should fail"),
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[3, 5, 9], &public).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.private, &public).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 4], &public).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_checksum_gadget() -> Result<(), Error> {
+    // The circuit closure runs the checksum gadget and constraints the result to be the
+    // expected checksum, placed as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   vector: &[u64],
+                   modulus: u64,
+                   checksum: u64|
+     -> Result<(), GadgetError> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let result = checksum_gadget(composer, &vector, modulus, 32)?;
+        composer.constrain_to_constant(
+            result.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(checksum)),
+        );
+        Ok(())
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    let test_cases: Vec<(Vec<u64>, u64)> = vec![
+        (vec![1, 2, 3, 4], 7),
+        (vec![1_000, 2_000, 3_000, 4_000], 256),
+        (vec![123_456, 0, 789, 42], 65_521),
+        (vec![5, 5, 5, 5], 20),
+    ];
+
+    for (vector, modulus) in test_cases.iter() {
+        let checksum = vector.iter().sum::<u64>() % modulus;
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), vector, *modulus, checksum).is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 4], *modulus, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+
+        // A wrong checksum should fail
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), vector, *modulus, checksum + 1).is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+        assert!(verifier.verify(&proof, &vk, &pi).is_err());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_reduce_tree() -> Result<(), Error> {
+    // The circuit closure reduces the vector both in a tree and in a linear chain, constraining
+    // them to be equal, and then applies the tree-based sum and product gadgets.
+    let circuit = |composer: &mut StandardComposer,
+                   vector: &[u64],
+                   expected_sum: u64,
+                   expected_product: u64|
+     -> Result<(), GadgetError> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let vars: Vec<Variable> = vector.iter().map(|elem| elem.var).collect();
+
+        let tree_sum = reduce_tree(composer, vars.clone(), ReduceOp::Add);
+        let tree_product = reduce_tree(composer, vars.clone(), ReduceOp::Mul);
+        let mut linear_sum = composer.zero_var();
+        let mut linear_product = composer.add_witness_to_circuit_description(BlsScalar::one());
+        for var in vars.iter() {
+            linear_sum = composer.add(
+                (BlsScalar::one(), linear_sum),
+                (BlsScalar::one(), *var),
+                BlsScalar::zero(),
+                None,
+            );
+            linear_product = composer.mul(
+                BlsScalar::one(),
+                linear_product,
+                *var,
+                BlsScalar::zero(),
+                None,
+            );
+        }
+        composer.assert_equal(tree_sum, linear_sum);
+        composer.assert_equal(tree_product, linear_product);
+
+        vector_sum_tree_gadget(composer, &vector, expected_sum)?;
+        vector_elements_product_tree_gadget(composer, &vector, BlsScalar::from(expected_product))
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        vector: Vec<u64>,
+        expected_sum: u64,
+        expected_product: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            vector: vec![2, 3, 4, 5, 6],
+            expected_sum: 20,
+            expected_product: 720,
+            expected: true,
+            desc: String::from("Odd amount of elements"),
+        },
+        TestCase {
+            vector: vec![2, 3, 4, 5],
+            expected_sum: 14,
+            expected_product: 120,
+            expected: true,
+            desc: String::from("Even amount of elements"),
+        },
+        TestCase {
+            vector: vec![2, 3, 4, 5],
+            expected_sum: 15,
+            expected_product: 120,
+            expected: false,
+            desc: String::from("Wrong sum, should fail"),
+        },
+        TestCase {
+            vector: vec![2, 3, 4, 5],
+            expected_sum: 14,
+            expected_product: 121,
+            expected: false,
+            desc: String::from("Wrong product, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(
+            prover.mut_cs(),
+            &case.vector,
+            case.expected_sum,
+            case.expected_product
+        )
+        .is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![0; case.vector.len()];
+        assert!(circuit(verifier.mut_cs(), &placeholder, 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_vector_product_tree_gadget() -> Result<(), Error> {
+    // The circuit closure runs both the linear and the tree-based vector product gadgets,
+    // which constraint the masked elements to be equal to the value.
+    let circuit = |composer: &mut StandardComposer,
+                   vector: &[u64],
+                   bits: &[u64],
+                   value: u64|
+     -> Result<(), GadgetError> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let bits: Vec<AllocatedScalar> = bits
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+        vector_product_gadget(composer, &vector, &bits, value)?;
+        vector_product_tree_gadget(composer, &vector, &bits, value)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // (vector, bits, value, expected)
+    let test_cases: Vec<(Vec<u64>, Vec<u64>, u64, bool)> = vec![
+        (vec![3, 7, 5, 9, 1], vec![0, 0, 1, 0, 0], 5, true),
+        (vec![3, 7, 5, 9], vec![0, 0, 0, 1], 9, true),
+        // The selected element differs from the value
+        (vec![3, 7, 5, 9, 1], vec![0, 1, 0, 0, 0], 5, false),
+    ];
+
+    for (vector, bits, value, expected) in test_cases.into_iter() {
+        // Proving
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &vector, &bits, value).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Verification
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![0; vector.len()];
+        assert!(circuit(verifier.mut_cs(), &placeholder, &placeholder, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    // Vectors of different lengths should err
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[3, 7, 5], &[0, 1], 7).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_enum_discriminant_gadget() -> Result<(), Error> {
+    // The circuit closure runs the enum discriminant gadget, which constraints the tag to be
+    // one of the variants.
+    let circuit = |composer: &mut StandardComposer, tag: u64| -> Result<(), GadgetError> {
+        let tag = AllocatedScalar::allocate(composer, BlsScalar::from(tag));
+        enum_discriminant_gadget(composer, tag, &[0, 1, 2, 7])
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    // (tag, expected)
+    let test_cases: Vec<(u64, bool)> = vec![
+        (0, true),
+        (1, true),
+        (2, true),
+        (7, true),
+        (3, false),
+        (u64::MAX, false),
+    ];
+
+    for (tag, expected) in test_cases.into_iter() {
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), tag).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_weighted_average_in_range_gadget() -> Result<(), Error> {
+    // The circuit closure runs the weighted average gadget, which constraints the weighted
+    // average of the values to lie in [40, 60).
+    let circuit = |composer: &mut StandardComposer,
+                   values: &[u64],
+                   weights: &[u64]|
+     -> Result<(), GadgetError> {
+        let values: Vec<AllocatedScalar> = values
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let weights: Vec<AllocatedScalar> = weights
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        weighted_average_in_range_gadget(composer, &values, &weights, 40, 60, 32)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        values: Vec<u64>,
+        weights: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            values: vec![50, 50, 50],
+            weights: vec![1, 2, 3],
+            expected: true,
+            desc: String::from("Constant values inside the range"),
+        },
+        TestCase {
+            values: vec![10, 70, 40],
+            weights: vec![1, 1, 2],
+            expected: true,
+            desc: String::from("Average of exactly the lower bound"),
+        },
+        TestCase {
+            values: vec![0, 100, 50],
+            weights: vec![1, 3, 0],
+            expected: false,
+            desc: String::from("Average above the range, should fail"),
+        },
+        TestCase {
+            values: vec![59, 61, 60],
+            weights: vec![1, 1, 1],
+            expected: false,
+            desc: String::from("Average of exactly the upper bound, should fail"),
+        },
+        TestCase {
+            values: vec![10, 20, 90],
+            weights: vec![5, 5, 1],
+            expected: false,
+            desc: String::from("Average below the range, should fail"),
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[1, 2, 3], &[1, 2]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.values, &case.weights).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 3], &[0; 3]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_member_satisfying_gadget() -> Result<(), Error> {
+    // The circuit closure runs the member satisfying gadget with a predicate constraining the
+    // matched value to fit in 8 bits.
+    let circuit = |composer: &mut StandardComposer,
+                   set: &[BlsScalar],
+                   value: BlsScalar|
+     -> Result<(), GadgetError> {
+        let value = AllocatedScalar::allocate(composer, value);
+        member_satisfying_gadget(composer, set, value, |composer, matched| {
+            RangeGadgets::range_gadget(composer, matched, 8)
+        })
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        value: BlsScalar,
+        expected: bool,
+        desc: String,
+    }
+
+    let set: Vec<BlsScalar> = vec![3u64, 200, 1_000]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            value: BlsScalar::from(200),
+            expected: true,
+            desc: String::from("Member satisfying the predicate"),
+        },
+        TestCase {
+            value: BlsScalar::from(1_000),
+            expected: false,
+            desc: String::from("Member not satisfying the predicate, should fail"),
+        },
+        TestCase {
+            value: BlsScalar::from(100),
+            expected: false,
+            desc: String::from("Non-member satisfying the predicate, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &set, case.value).is_ok());
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &set, BlsScalar::zero()).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_sparsity_gadget() -> Result<(), Error> {
+    // The circuit closure runs the sparsity gadget, which constraints the vector to contain
+    // at most 2 non-zero elements.
+    let circuit = |composer: &mut StandardComposer, vector: &[u64]| -> Result<(), GadgetError> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        sparsity_gadget(composer, &vector, 2, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        vector: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            vector: vec![0, 0, 0, 0, 0],
+            expected: true,
+            desc: String::from("All zeros"),
+        },
+        TestCase {
+            vector: vec![0, 7, 0, 0, 0],
+            expected: true,
+            desc: String::from("Below the sparsity bound"),
+        },
+        TestCase {
+            vector: vec![0, 7, 0, 9, 0],
+            expected: true,
+            desc: String::from("At the sparsity bound"),
+        },
+        TestCase {
+            vector: vec![1, 7, 0, 9, 0],
+            expected: false,
+            desc: String::from("Above the sparsity bound, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.vector).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 5]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_set_delta_gadget() -> Result<(), Error> {
+    // The circuit closure runs the set delta gadget, which constraints `after` to be
+    // `before` with `removed` taken out and `added` put in.
+    let circuit = |composer: &mut StandardComposer,
+                   before: &[u64],
+                   after: &[u64],
+                   added: &[u64],
+                   removed: &[u64]|
+     -> Result<(), GadgetError> {
+        let mut allocate = |values: &[u64]| -> Vec<AllocatedScalar> {
+            values
+                .iter()
+                .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+                .collect()
+        };
+        let before = allocate(before);
+        let after = allocate(after);
+        let added = allocate(added);
+        let removed = allocate(removed);
+        set_delta_gadget(composer, &before, &after, &added, &removed)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        after: Vec<u64>,
+        added: Vec<u64>,
+        removed: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let before = vec![3, 5, 8, 13];
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            after: vec![21, 3, 8, 34],
+            added: vec![34, 21],
+            removed: vec![13, 5],
+            expected: true,
+            desc: String::from("Valid transition adding and removing two elements"),
+        },
+        TestCase {
+            after: vec![5, 8, 13, 3],
+            added: vec![5, 1],
+            removed: vec![1, 5],
+            expected: true,
+            desc: String::from("Valid transition leaving the set unchanged"),
+        },
+        TestCase {
+            after: vec![21, 3, 8, 34],
+            added: vec![34, 21],
+            removed: vec![13, 55],
+            expected: false,
+            desc: String::from("Removed element which isn't in the set, should fail"),
+        },
+        TestCase {
+            after: vec![21, 3, 8, 34],
+            added: vec![34, 22],
+            removed: vec![13, 5],
+            expected: false,
+            desc: String::from("Wrong claimed added element, should fail"),
+        },
+    ];
+
+    // Inconsistent sizes are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &before, &[3, 5, 8], &[13], &[]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(
+            prover.mut_cs(),
+            &before,
+            &case.after,
+            &case.added,
+            &case.removed
+        )
+        .is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 4], &[0; 4], &[0; 2], &[0; 2]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_bounded_subset_sum_gadget() -> Result<(), Error> {
+    // The circuit closure runs the bounded subset sum gadget, which constraints the
+    // selected values to add up to the target, selecting at most 3 of them.
+    let circuit = |composer: &mut StandardComposer,
+                   values: &[u64],
+                   selectors: &[u64],
+                   target: u64|
+     -> Result<(), GadgetError> {
+        let values: Vec<AllocatedScalar> = values
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let selectors: Vec<AllocatedScalar> = selectors
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let target = AllocatedScalar::allocate(composer, BlsScalar::from(target));
+        bounded_subset_sum_gadget(composer, &values, &selectors, target, 3, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        selectors: Vec<u64>,
+        target: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let values = vec![5, 11, 20, 3, 8];
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            selectors: vec![1, 0, 1, 0, 1],
+            target: 33,
+            expected: true,
+            desc: String::from("Valid subset at the count bound"),
+        },
+        TestCase {
+            selectors: vec![0, 0, 0, 0, 0],
+            target: 0,
+            expected: true,
+            desc: String::from("Empty subset"),
+        },
+        TestCase {
+            selectors: vec![1, 0, 1, 0, 1],
+            target: 34,
+            expected: false,
+            desc: String::from("Wrong sum, should fail"),
+        },
+        TestCase {
+            selectors: vec![1, 1, 0, 1, 1],
+            target: 27,
+            expected: false,
+            desc: String::from("Exceeding the count bound, should fail"),
+        },
+        TestCase {
+            selectors: vec![0, 3, 0, 0, 0],
+            target: 33,
+            expected: false,
+            desc: String::from("Non-boolean selector, should fail"),
+        },
+    ];
+
+    // Mismatching lengths are rejected
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &values, &[1, 0], 5).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &values, &case.selectors, case.target).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 5], &[0; 5], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_member_and_not_member_gadget() -> Result<(), Error> {
+    // The circuit closure runs the member and not member gadget, which constraints the
+    // value to be in the allowlist and not in the denylist.
+    let circuit = |composer: &mut StandardComposer,
+                   allow: &[BlsScalar],
+                   deny: &[BlsScalar],
+                   value: BlsScalar|
+     -> Result<(), GadgetError> {
+        let value = AllocatedScalar::allocate(composer, value);
+        member_and_not_member_gadget(composer, value, allow, deny)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let allow: Vec<BlsScalar> = vec![3u64, 17, 42, 99]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+    let deny: Vec<BlsScalar> = vec![42u64, 1_000]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    // Allowed and not denied
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &allow, &deny, BlsScalar::from(17)).is_ok());
+    let pi = prover.mut_cs().construct_dense_pi_vec();
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::new(b"testing");
+    assert!(circuit(verifier.mut_cs(), &allow, &deny, BlsScalar::zero()).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+
+    // Both allowed and denied: the prover can't compute the non-membership witness
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &allow, &deny, BlsScalar::from(42)).is_err());
+
+    // Neither allowed nor denied: the membership proof fails
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &allow, &deny, BlsScalar::from(5)).is_ok());
+    let pi = prover.mut_cs().construct_dense_pi_vec();
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    let mut verifier = Verifier::new(b"testing");
+    assert!(circuit(verifier.mut_cs(), &allow, &deny, BlsScalar::zero()).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier.verify(&proof, &vk, &pi).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_count_matching_at_least_gadget() -> Result<(), Error> {
+    // The circuit closure runs the count matching gadget, which constraints at least 3
+    // elements of the vector to be equal to the value.
+    let circuit =
+        |composer: &mut StandardComposer, vector: &[u64], value: u64| -> Result<(), GadgetError> {
+            let vector: Vec<AllocatedScalar> = vector
+                .iter()
+                .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+                .collect();
+            let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+            count_matching_at_least_gadget(composer, &vector, value, 3, 8)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        vector: Vec<u64>,
+        value: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            vector: vec![1, 0, 1, 1, 0],
+            value: 1,
+            expected: true,
+            desc: String::from("At the threshold"),
+        },
+        TestCase {
+            vector: vec![1, 1, 1, 1, 0],
+            value: 1,
+            expected: true,
+            desc: String::from("Above the threshold"),
+        },
+        TestCase {
+            vector: vec![1, 0, 1, 1, 0],
+            value: 0,
+            expected: false,
+            desc: String::from("Below the threshold, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.vector, case.value).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 5], 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_vector_product_with_accumulator_gadget() -> Result<(), Error> {
+    // The circuit closure selects the value out of the vector with the bits, and reuses the
+    // returned accumulator to constrain twice its value to be the Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   vector: &[u64],
+                   bits: &[u64],
+                   value: u64,
+                   doubled: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let vector: Vec<AllocatedScalar> = vector
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let bits: Vec<AllocatedScalar> = bits
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let value = AllocatedScalar::allocate(composer, BlsScalar::from(value));
+        let accumulator = vector_product_with_accumulator_gadget(composer, &vector, &bits, value)?;
+        let doubled_var = composer.add(
+            (BlsScalar::from(2), accumulator.var),
+            (BlsScalar::zero(), accumulator.var),
+            BlsScalar::zero(),
+            None,
+        );
+        composer.constrain_to_constant(
+            doubled_var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(doubled)),
+        );
+        Ok(accumulator.scalar)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    let vector = [5u64, 7, 9];
+
+    // (bits, value, doubled, expected)
+    let test_cases = vec![
+        (vec![0u64, 1, 0], 7, 14, true),
+        (vec![0u64, 0, 1], 9, 18, true),
+        (vec![0u64, 1, 0], 7, 16, false),
+        (vec![0u64, 1, 0], 9, 18, false),
+    ];
+
+    for (bits, value, doubled, expected) in test_cases.into_iter() {
+        let mut prover = Prover::new(b"testing");
+        let accumulator =
+            circuit(prover.mut_cs(), &vector, &bits, value, doubled).expect("Vector product error");
+        assert_eq!(
+            accumulator,
+            vector
+                .iter()
+                .zip(bits.iter())
+                .fold(BlsScalar::zero(), |acc, (x, b)| acc
+                    + BlsScalar::from(x * b))
+        );
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 3], &[0; 3], 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    // Vectors of different lengths should err
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[1, 2, 3], &[0, 1], 2, 4).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_within_moving_average_gadget() -> Result<(), Error> {
+    // The circuit closure runs the moving average gadget, which constraints every point
+    // after the first 3 to be within 5 of the average of the 3 preceding ones.
+    let circuit = |composer: &mut StandardComposer, series: &[u64]| -> Result<(), GadgetError> {
+        let series: Vec<AllocatedScalar> = series
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        within_moving_average_gadget(composer, &series, 3, 5, 16)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    struct TestCase {
+        series: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            series: vec![100, 102, 98, 101, 104, 100, 97],
+            expected: true,
+            desc: String::from("Smooth series"),
+        },
+        TestCase {
+            series: vec![100, 100, 100, 105, 98, 100, 100],
+            expected: true,
+            desc: String::from("Point at the tolerance bound"),
+        },
+        TestCase {
+            series: vec![100, 102, 98, 101, 140, 100, 97],
+            expected: false,
+            desc: String::from("Upwards spike, should fail"),
+        },
+        TestCase {
+            series: vec![100, 102, 98, 101, 104, 100, 60],
+            expected: false,
+            desc: String::from("Downwards spike, should fail"),
+        },
+    ];
+
+    // An empty window is rejected
+    let mut prover = Prover::new(b"testing");
+    let series = vec![AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::one()); 2];
+    assert!(within_moving_average_gadget(prover.mut_cs(), &series, 0, 5, 16).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.series).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 7]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_monotone_table_lookup_gadget() -> Result<(), Error> {
+    // Calibration table mapping sensor readings into temperatures
+    let table_x: Vec<BlsScalar> = vec![0u64, 10, 25, 60, 100]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+    let table_y: Vec<BlsScalar> = vec![250u64, 265, 240, 310, 400]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    // The circuit closure runs the monotone table lookup gadget, which constraints (x, y)
+    // to be one of the table rows.
+    let circuit = |composer: &mut StandardComposer,
+                   x: u64,
+                   y: u64,
+                   table_x: &[BlsScalar]|
+     -> Result<(), GadgetError> {
+        let x = AllocatedScalar::allocate(composer, BlsScalar::from(x));
+        let y = AllocatedScalar::allocate(composer, BlsScalar::from(y));
+        monotone_table_lookup_gadget(composer, x, y, table_x, &table_y, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 8, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 7)?;
+
+    struct TestCase {
+        x: u64,
+        y: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            x: 0,
+            y: 250,
+            expected: true,
+            desc: String::from("First row"),
+        },
+        TestCase {
+            x: 25,
+            y: 240,
+            expected: true,
+            desc: String::from("Middle row"),
+        },
+        TestCase {
+            x: 100,
+            y: 400,
+            expected: true,
+            desc: String::from("Last row"),
+        },
+        TestCase {
+            x: 25,
+            y: 265,
+            expected: false,
+            desc: String::from("Output of another row, should fail"),
+        },
+        TestCase {
+            x: 30,
+            y: 240,
+            expected: false,
+            desc: String::from("Input between rows, should fail"),
+        },
+    ];
+
+    // Invalid tables are rejected
+    let mut prover = Prover::new(b"testing");
+    let unsorted = vec![table_x[1], table_x[0], table_x[2], table_x[3], table_x[4]];
+    assert!(circuit(prover.mut_cs(), 0, 250, &unsorted).is_err());
+    assert!(circuit(prover.mut_cs(), 0, 250, &table_x[..4]).is_err());
+    let mut too_large = table_x.clone();
+    too_large[4] = BlsScalar::from(256);
+    assert!(circuit(prover.mut_cs(), 0, 250, &too_large).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), case.x, case.y, &table_x).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0, 0, &table_x).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_valid_coloring_gadget() -> Result<(), Error> {
+    // Cycle of 5 vertices with a chord, which needs 3 colors
+    let edges = [(0, 1), (1, 2), (2, 3), (3, 4), (4, 0), (0, 2)];
+
+    // The circuit closure runs the valid coloring gadget, which constraints the colors
+    // to be a 3-coloring of the graph.
+    let circuit = |composer: &mut StandardComposer, colors: &[u64]| -> Result<(), GadgetError> {
+        let colors: Vec<AllocatedScalar> = colors
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        valid_coloring_gadget(composer, &colors, &edges, 3, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        colors: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            colors: vec![0, 1, 2, 0, 1],
+            expected: true,
+            desc: String::from("Valid coloring"),
+        },
+        TestCase {
+            colors: vec![2, 0, 1, 2, 0],
+            expected: true,
+            desc: String::from("Another valid coloring"),
+        },
+        TestCase {
+            colors: vec![0, 1, 3, 0, 1],
+            expected: false,
+            desc: String::from("Color out of range, should fail"),
+        },
+    ];
+
+    // Monochromatic edges can't be proven
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[0, 1, 2, 1, 1]).is_err());
+
+    // Edges referencing missing vertices are rejected
+    let mut prover = Prover::new(b"testing");
+    let colors = vec![AllocatedScalar::allocate(
+        prover.mut_cs(),
+        BlsScalar::zero(),
+    )];
+    assert!(valid_coloring_gadget(prover.mut_cs(), &colors, &[(0, 1)], 3, 8).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.colors).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Since the endpoints of each edge must differ, a valid coloring is used as placeholder
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0, 1, 2, 0, 1]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_all_different_groups_gadget() -> Result<(), Error> {
+    // 4x4 Sudoku, whose groups are its rows, columns and 2x2 boxes
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    for i in 0..4 {
+        groups.push((0..4).map(|j| 4 * i + j).collect());
+        groups.push((0..4).map(|j| 4 * j + i).collect());
+    }
+    for (row, col) in [(0, 0), (0, 2), (2, 0), (2, 2)].iter() {
+        groups.push(vec![
+            4 * row + col,
+            4 * row + col + 1,
+            4 * (row + 1) + col,
+            4 * (row + 1) + col + 1,
+        ]);
+    }
+
+    // The circuit closure runs the all different groups gadget over the grid cells.
+    let circuit = |composer: &mut StandardComposer, grid: &[u64]| -> Result<(), GadgetError> {
+        let cells: Vec<AllocatedScalar> = grid
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        all_different_groups_gadget(composer, &cells, &groups)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 10, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 9)?;
+
+    let solution = [1, 2, 3, 4, 3, 4, 1, 2, 2, 1, 4, 3, 4, 3, 2, 1];
+
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &solution).is_ok());
+    prover.preprocess(&ck)?;
+    let proof = prover.prove(&ck)?;
+
+    // Since all the groups must be distinct, a valid solution is used as placeholder
+    let placeholder = [4, 3, 2, 1, 2, 1, 4, 3, 3, 4, 1, 2, 1, 2, 3, 4];
+    let mut verifier = Verifier::new(b"testing");
+    assert!(circuit(verifier.mut_cs(), &placeholder).is_ok());
+    verifier.preprocess(&ck)?;
+    assert!(verifier
+        .verify(&proof, &vk, &vec![BlsScalar::zero()])
+        .is_ok());
+
+    // Rows and columns are fine, but the top-left box contains a duplicate
+    let invalid = [1, 2, 3, 4, 2, 3, 4, 1, 3, 4, 1, 2, 4, 1, 2, 3];
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &invalid).is_err());
+
+    // Groups referencing missing cells are rejected
+    let mut prover = Prover::new(b"testing");
+    let cells = vec![AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::one()); 2];
+    assert!(all_different_groups_gadget(prover.mut_cs(), &cells, &[vec![0, 2]]).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_partition_gadget() -> Result<(), Error> {
+    // The circuit closure runs the partition gadget, which constrains both parts to
+    // split the public universe.
+    let circuit = |composer: &mut StandardComposer,
+                   part_a: &[u64],
+                   part_b: &[u64],
+                   universe: &[BlsScalar]|
+     -> Result<(), GadgetError> {
+        let part_a: Vec<AllocatedScalar> = part_a
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        let part_b: Vec<AllocatedScalar> = part_b
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        partition_gadget(composer, &part_a, &part_b, universe)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    let universe: Vec<BlsScalar> = vec![2u64, 3, 5, 7, 11]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    struct TestCase {
+        part_a: Vec<u64>,
+        part_b: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            part_a: vec![7, 2],
+            part_b: vec![11, 3, 5],
+            expected: true,
+            desc: String::from("Valid partition"),
+        },
+        TestCase {
+            part_a: vec![7, 2],
+            part_b: vec![11, 3, 13],
+            expected: false,
+            desc: String::from("Missing element of the universe, should fail"),
+        },
+    ];
+
+    // Overlapping parts can't be proven
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[7, 2], &[11, 2, 5], &universe).is_err());
+
+    // The sizes of the parts must add up to the size of the universe
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[7, 2], &[11, 3], &universe).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.part_a, &case.part_b, &universe).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        // Since the parts must be disjoint, a valid partition is used as placeholder
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[2, 3], &[5, 7, 11], &universe).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_weighted_selection_gadget() -> Result<(), Error> {
+    // Public choices and their associated weights
+    let choices: Vec<BlsScalar> = vec![3u64, 14, 15, 92]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+    let weights: Vec<BlsScalar> = vec![10u64, 20, 30, 40]
+        .iter()
+        .map(|x| BlsScalar::from(*x))
+        .collect();
+
+    // The circuit closure runs the weighted selection gadget, which constraints `chosen`
+    // to be one of the choices and `weight_out` to be its weight.
+    let circuit = |composer: &mut StandardComposer,
+                   chosen: u64,
+                   weight_out: u64,
+                   weights: &[BlsScalar]|
+     -> Result<(), GadgetError> {
+        let chosen = AllocatedScalar::allocate(composer, BlsScalar::from(chosen));
+        let weight_out = AllocatedScalar::allocate(composer, BlsScalar::from(weight_out));
+        weighted_selection_gadget(composer, &choices, weights, chosen, weight_out)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    struct TestCase {
+        chosen: u64,
+        weight_out: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let mut test_cases: Vec<TestCase> = vec![3u64, 14, 15, 92]
+        .into_iter()
+        .zip(vec![10u64, 20, 30, 40])
+        .map(|(chosen, weight_out)| TestCase {
+            chosen,
+            weight_out,
+            expected: true,
+            desc: format!("Choice {} with weight {}", chosen, weight_out),
+        })
+        .collect();
+    test_cases.push(TestCase {
+        chosen: 14,
+        weight_out: 30,
+        expected: false,
+        desc: String::from("Weight of another choice, should fail"),
+    });
+    test_cases.push(TestCase {
+        chosen: 7,
+        weight_out: 10,
+        expected: false,
+        desc: String::from("Non-member, should fail"),
+    });
+
+    // There must be one weight per choice
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), 3, 10, &weights[..3]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), case.chosen, case.weight_out, &weights).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), 0, 0, &weights).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_edit_distance_at_most_gadget() -> Result<(), Error> {
+    // The circuit closure runs the edit distance gadget, which constraints the
+    // Levenshtein distance between both sequences to be at most 2.
+    let circuit =
+        |composer: &mut StandardComposer, a: &[u8], b: &[u8]| -> Result<(), GadgetError> {
+            let a: Vec<AllocatedScalar> = a
+                .iter()
+                .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x as u64)))
+                .collect();
+            let b: Vec<AllocatedScalar> = b
+                .iter()
+                .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x as u64)))
+                .collect();
+            edit_distance_at_most_gadget(composer, &a, &b, 2, 4)
+        };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 11, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 10)?;
+
+    struct TestCase {
+        a: &'static [u8],
+        b: &'static [u8],
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            a: b"dusk",
+            b: b"dusk",
+            expected: true,
+            desc: String::from("Equal sequences"),
+        },
+        TestCase {
+            a: b"dusk",
+            b: b"duck",
+            expected: true,
+            desc: String::from("Single substitution"),
+        },
+        TestCase {
+            a: b"dusk",
+            b: b"usks",
+            expected: true,
+            desc: String::from("Deletion and insertion"),
+        },
+        TestCase {
+            a: b"dusk",
+            b: b"ksud",
+            expected: false,
+            desc: String::from("Distance beyond the bound, should fail"),
+        },
+        TestCase {
+            a: b"dusk",
+            b: b"plon",
+            expected: false,
+            desc: String::from("Completely different sequences, should fail"),
+        },
+    ];
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), case.a, case.b).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 4], &[0; 4]).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_is_topologically_ordered_gadget() -> Result<(), Error> {
+    // The circuit closure runs the topological order gadget, which constraints the ranks
+    // to increase along every edge of the graph.
+    let circuit = |composer: &mut StandardComposer,
+                   order: &[u64],
+                   edges: &[(usize, usize)]|
+     -> Result<(), GadgetError> {
+        let order: Vec<AllocatedScalar> = order
+            .iter()
+            .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+            .collect();
+        is_topologically_ordered_gadget(composer, &order, edges, 8)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    // Diamond-shaped DAG and the same graph with its last edge reversed into a cycle
+    let dag: &[(usize, usize)] = &[(0, 1), (0, 2), (1, 3), (2, 3), (3, 4)];
+    let cyclic: &[(usize, usize)] = &[(0, 1), (0, 2), (1, 3), (2, 3), (3, 0)];
+
+    struct TestCase {
+        order: Vec<u64>,
+        edges: &'static [(usize, usize)],
+        expected: bool,
+        desc: String,
+    }
+
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            order: vec![0, 1, 2, 3, 4],
+            edges: dag,
+            expected: true,
+            desc: String::from("Valid order of the DAG"),
+        },
+        TestCase {
+            order: vec![10, 42, 11, 50, 200],
+            edges: dag,
+            expected: true,
+            desc: String::from("Valid order of the DAG with gaps"),
+        },
+        TestCase {
+            order: vec![0, 2, 1, 1, 4],
+            edges: dag,
+            expected: false,
+            desc: String::from("Order not increasing along an edge, should fail"),
+        },
+        TestCase {
+            order: vec![0, 1, 2, 3, 4],
+            edges: cyclic,
+            expected: false,
+            desc: String::from("Cyclic graph, should fail"),
+        },
+        TestCase {
+            order: vec![3, 1, 2, 0, 4],
+            edges: cyclic,
+            expected: false,
+            desc: String::from("Cyclic graph with another order, should fail"),
+        },
+    ];
+
+    // The edges must reference existing nodes
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &[0, 1, 2, 3, 4], &[(0, 5)]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &case.order, case.edges).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[0; 5], case.edges).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_filter_consistency_gadget() -> Result<(), Error> {
+    // The circuit closure runs the filter consistency gadget, which constraints the
+    // output to hold the input elements whose keep bit is set.
+    let circuit = |composer: &mut StandardComposer,
+                   input: &[u64],
+                   keep_bits: &[u64],
+                   output: &[u64]|
+     -> Result<(), GadgetError> {
+        let mut allocate = |values: &[u64]| -> Vec<AllocatedScalar> {
+            values
+                .iter()
+                .map(|x| AllocatedScalar::allocate(composer, BlsScalar::from(*x)))
+                .collect()
+        };
+        let input = allocate(input);
+        let keep_bits = allocate(keep_bits);
+        let output = allocate(output);
+        filter_consistency_gadget(composer, &input, &keep_bits, &output)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 9, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 8)?;
+
+    struct TestCase {
+        keep_bits: Vec<u64>,
+        output: Vec<u64>,
+        expected: bool,
+        desc: String,
+    }
+
+    let input = vec![5, 8, 13, 21, 34];
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            keep_bits: vec![1, 1, 1, 1, 1],
+            output: vec![5, 8, 13, 21, 34],
+            expected: true,
+            desc: String::from("Keeping all the elements"),
+        },
+        TestCase {
+            keep_bits: vec![0, 0, 0, 0, 0],
+            output: vec![],
+            expected: true,
+            desc: String::from("Keeping none of the elements"),
+        },
+        TestCase {
+            keep_bits: vec![0, 1, 0, 1, 1],
+            output: vec![8, 21, 34],
+            expected: true,
+            desc: String::from("Keeping a subset of the elements"),
+        },
+        TestCase {
+            keep_bits: vec![0, 1, 0, 1, 1],
+            output: vec![21, 8, 34],
+            expected: false,
+            desc: String::from("Reordered output, should fail"),
+        },
+        TestCase {
+            keep_bits: vec![0, 1, 1, 1, 1],
+            output: vec![8, 21, 34],
+            expected: false,
+            desc: String::from("Missing kept element, should fail"),
+        },
+        TestCase {
+            keep_bits: vec![0, 1, 0, 2, 0],
+            output: vec![8, 21, 21],
+            expected: false,
+            desc: String::from("Non-bit keep flag, should fail"),
+        },
+    ];
+
+    // There must be one keep bit per input element
+    let mut prover = Prover::new(b"testing");
+    assert!(circuit(prover.mut_cs(), &input, &[1, 1], &[5, 8]).is_err());
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        assert!(circuit(prover.mut_cs(), &input, &case.keep_bits, &case.output).is_ok());
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        let placeholder = vec![0; case.output.len()];
+        assert!(circuit(verifier.mut_cs(), &[0; 5], &[0; 5], &placeholder).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_ok());
+        } else {
+            assert!(verifier
+                .verify(&proof, &vk, &vec![BlsScalar::zero()])
+                .is_err());
+        }
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_lookup_or_default_gadget() -> Result<(), Error> {
+    // The circuit closure runs the lookup_or_default gadget over the private key-value
+    // pairs, and constraints the result to be equal to the expected one, which is set
+    // as Public Input.
+    let circuit = |composer: &mut StandardComposer,
+                   pairs: &[(u64, u64)],
+                   key: u64,
+                   expected: u64|
+     -> Result<BlsScalar, GadgetError> {
+        let keys: Vec<AllocatedScalar> = pairs
+            .iter()
+            .map(|(k, _)| AllocatedScalar::allocate(composer, BlsScalar::from(*k)))
+            .collect();
+        let values: Vec<AllocatedScalar> = pairs
+            .iter()
+            .map(|(_, v)| AllocatedScalar::allocate(composer, BlsScalar::from(*v)))
+            .collect();
+        let key = AllocatedScalar::allocate(composer, BlsScalar::from(key));
+        let result = lookup_or_default_gadget(composer, &keys, &values, key, BlsScalar::from(999))?;
+        composer.constrain_to_constant(
+            result.var,
+            BlsScalar::zero(),
+            Some(-BlsScalar::from(expected)),
+        );
+        Ok(result.scalar)
+    };
+
+    // Generate Composer & Public Parameters
+    let pub_params = PublicParameters::setup(1 << 7, &mut rand::thread_rng())?;
+    let (ck, vk) = pub_params.trim(1 << 6)?;
+
+    struct TestCase {
+        key: u64,
+        result: u64,
+        expected: bool,
+        desc: String,
+    }
+
+    let pairs = vec![(3, 30), (7, 70), (11, 0), (7, 71)];
+    let test_cases: Vec<TestCase> = vec![
+        TestCase {
+            key: 3,
+            result: 30,
+            expected: true,
+            desc: String::from("Present key"),
+        },
+        TestCase {
+            key: 11,
+            result: 0,
+            expected: true,
+            desc: String::from("Present key with a zero value"),
+        },
+        TestCase {
+            key: 7,
+            result: 70,
+            expected: true,
+            desc: String::from("Repeated key returns its first value"),
+        },
+        TestCase {
+            key: 5,
+            result: 999,
+            expected: true,
+            desc: String::from("Absent key returns the default"),
+        },
+        TestCase {
+            key: 5,
+            result: 0,
+            expected: false,
+            desc: String::from("Absent key with a non-default result, should fail"),
+        },
+        TestCase {
+            key: 3,
+            result: 999,
+            expected: false,
+            desc: String::from("Present key with the default result, should fail"),
+        },
+    ];
+
+    // There must be one value per key
+    let mut prover = Prover::new(b"testing");
+    let keys = vec![AllocatedScalar::allocate(prover.mut_cs(), BlsScalar::one())];
+    let key = keys[0];
+    assert!(
+        lookup_or_default_gadget(prover.mut_cs(), &keys, &vec![], key, BlsScalar::zero()).is_err()
+    );
+
+    for case in test_cases.iter() {
+        println!("{}", case.desc);
+
+        let mut prover = Prover::new(b"testing");
+        let result = circuit(prover.mut_cs(), &pairs, case.key, case.result).expect("Lookup error");
+        assert_eq!(result == BlsScalar::from(case.result), case.expected);
+        let pi = prover.mut_cs().construct_dense_pi_vec();
+        prover.preprocess(&ck)?;
+        let proof = prover.prove(&ck)?;
+
+        let mut verifier = Verifier::new(b"testing");
+        assert!(circuit(verifier.mut_cs(), &[(0, 0); 4], 0, 0).is_ok());
+        verifier.preprocess(&ck)?;
+        if case.expected {
+            assert!(verifier.verify(&proof, &vk, &pi).is_ok());
+        } else {
+            assert!(verifier.verify(&proof, &vk, &pi).is_err());
+        }
+    }
+
+    Ok(())
+}